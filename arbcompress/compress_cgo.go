@@ -14,9 +14,75 @@ package arbcompress
 */
 import "C"
 import (
+	"errors"
 	"fmt"
+	"sync"
 )
 
+var errDictionaryUnsupported = errors.New("brotli dictionaries need the brotli_dictionary build tag")
+
+var chainDictionary struct {
+	sync.RWMutex
+	data []byte
+}
+
+// SetDictionary sets the chain-specific dictionary, which may only happen once.
+// It fails unless built with the brotli_dictionary tag.
+func SetDictionary(dictionary []byte) error {
+	if !dictionarySupported {
+		return errDictionaryUnsupported
+	}
+	if len(dictionary) == 0 {
+		return errors.New("empty brotli dictionary")
+	}
+	chainDictionary.Lock()
+	defer chainDictionary.Unlock()
+	if chainDictionary.data != nil {
+		return errors.New("brotli dictionary already set")
+	}
+	chainDictionary.data = append([]byte{}, dictionary...)
+	return nil
+}
+
+// dictionaryBytes returns the contents of a dictionary, or nil when none applies.
+func dictionaryBytes(dictionary Dictionary) ([]byte, error) {
+	switch dictionary {
+	case EmptyDictionary:
+		return nil, nil
+	case ChainDictionary:
+		chainDictionary.RLock()
+		defer chainDictionary.RUnlock()
+		if chainDictionary.data == nil {
+			return nil, errors.New("brotli dictionary was never set")
+		}
+		return chainDictionary.data, nil
+	default:
+		return nil, fmt.Errorf("unknown brotli dictionary %d", dictionary)
+	}
+}
+
+func DecompressWithDictionary(input []byte, maxSize int, dictionary Dictionary) ([]byte, error) {
+	dict, err := dictionaryBytes(dictionary)
+	if err != nil {
+		return nil, err
+	}
+	if dict == nil {
+		return Decompress(input, maxSize)
+	}
+	return decompressWithDictionary(input, maxSize, dict)
+}
+
+func CompressWithDictionary(input []byte, level int, dictionary Dictionary) ([]byte, error) {
+	dict, err := dictionaryBytes(dictionary)
+	if err != nil {
+		return nil, err
+	}
+	if dict == nil {
+		return compressLevel(input, level)
+	}
+	return compressWithDictionary(input, level, dict)
+}
+
 func Decompress(input []byte, maxSize int) ([]byte, error) {
 	outbuf := make([]byte, maxSize)
 	outsize := C.size_t(maxSize)
@@ -6,6 +6,14 @@ package arbcompress
 const LEVEL_WELL = 11
 const WINDOW_SIZE = 22 // BROTLI_DEFAULT_WINDOW
 
+// Dictionary ids must be kept in sync with `arbitrator/arbutil/src/brotli.rs`.
+type Dictionary uint32
+
+const (
+	EmptyDictionary Dictionary = iota
+	ChainDictionary
+)
+
 func compressedBufferSizeFor(length int) int {
 	return length + (length>>10)*8 + 64 // actual limit is: length + (length >> 14) * 4 + 6
 }
@@ -0,0 +1,101 @@
+// Copyright 2024, Offchain Labs, Inc.
+// For license information, see https://github.com/nitro/blob/master/LICENSE
+
+//go:build !js && brotli_dictionary
+// +build !js,brotli_dictionary
+
+package arbcompress
+
+/*
+#cgo CFLAGS: -g -Wall -I${SRCDIR}/../target/include/
+#include "brotli/encode.h"
+#include "brotli/decode.h"
+
+static BROTLI_BOOL CompressWithDictionary(
+	int level, int lgwin, size_t input_size, const uint8_t* input,
+	size_t* output_size, uint8_t* output, size_t dict_size, const uint8_t* dict
+) {
+	BrotliEncoderPreparedDictionary* prepared = BrotliEncoderPrepareDictionary(
+		BROTLI_SHARED_DICTIONARY_RAW, dict_size, dict, level, NULL, NULL, NULL
+	);
+	if (prepared == NULL) {
+		return BROTLI_FALSE;
+	}
+	BrotliEncoderState* state = BrotliEncoderCreateInstance(NULL, NULL, NULL);
+	size_t available_out = *output_size;
+	BROTLI_BOOL ok = state != NULL
+		&& BrotliEncoderSetParameter(state, BROTLI_PARAM_MODE, BROTLI_MODE_GENERIC)
+		&& BrotliEncoderSetParameter(state, BROTLI_PARAM_QUALITY, level)
+		&& BrotliEncoderSetParameter(state, BROTLI_PARAM_LGWIN, lgwin)
+		&& BrotliEncoderAttachPreparedDictionary(state, prepared)
+		&& BrotliEncoderCompressStream(
+			state, BROTLI_OPERATION_FINISH, &input_size, &input, &available_out, &output, NULL
+		)
+		&& BrotliEncoderIsFinished(state);
+
+	// the encoder may reference the dictionary, so it must go first
+	if (state != NULL) {
+		BrotliEncoderDestroyInstance(state);
+	}
+	BrotliEncoderDestroyPreparedDictionary(prepared);
+	*output_size -= available_out;
+	return ok;
+}
+
+static BrotliDecoderResult DecompressWithDictionary(
+	size_t input_size, const uint8_t* input,
+	size_t* output_size, uint8_t* output, size_t dict_size, const uint8_t* dict
+) {
+	BrotliDecoderState* state = BrotliDecoderCreateInstance(NULL, NULL, NULL);
+	if (state == NULL) {
+		return BROTLI_DECODER_RESULT_ERROR;
+	}
+	BrotliDecoderResult result = BROTLI_DECODER_RESULT_ERROR;
+	size_t available_out = *output_size;
+	if (BrotliDecoderAttachDictionary(state, BROTLI_SHARED_DICTIONARY_RAW, dict_size, dict)) {
+		result = BrotliDecoderDecompressStream(
+			state, &input_size, &input, &available_out, &output, NULL
+		);
+	}
+	BrotliDecoderDestroyInstance(state);
+	*output_size -= available_out;
+	return result;
+}
+*/
+import "C"
+import "fmt"
+
+// The shared-dictionary API needs brotli 1.1.0 or newer.
+const dictionarySupported = true
+
+func bytesPtr(data []byte) *C.uint8_t {
+	if len(data) == 0 {
+		return nil
+	}
+	return (*C.uint8_t)(&data[0])
+}
+
+func decompressWithDictionary(input []byte, maxSize int, dict []byte) ([]byte, error) {
+	outbuf := make([]byte, maxSize)
+	outsize := C.size_t(maxSize)
+	res := C.DecompressWithDictionary(C.size_t(len(input)), bytesPtr(input), &outsize, bytesPtr(outbuf), C.size_t(len(dict)), bytesPtr(dict))
+	if res != C.BROTLI_DECODER_RESULT_SUCCESS {
+		return nil, fmt.Errorf("failed decompression: %d", res)
+	}
+	if int(outsize) > maxSize {
+		return nil, fmt.Errorf("result too large: %d", outsize)
+	}
+	return outbuf[:outsize], nil
+}
+
+func compressWithDictionary(input []byte, level int, dict []byte) ([]byte, error) {
+	maxOutSize := compressedBufferSizeFor(len(input))
+	outbuf := make([]byte, maxOutSize)
+	outSize := C.size_t(maxOutSize)
+	res := C.CompressWithDictionary(C.int(level), C.BROTLI_DEFAULT_WINDOW, C.size_t(len(input)), bytesPtr(input),
+		&outSize, bytesPtr(outbuf), C.size_t(len(dict)), bytesPtr(dict))
+	if res != 1 {
+		return nil, fmt.Errorf("failed compression: %d", res)
+	}
+	return outbuf[:outSize], nil
+}
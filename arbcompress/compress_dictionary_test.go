@@ -0,0 +1,52 @@
+// Copyright 2024, Offchain Labs, Inc.
+// For license information, see https://github.com/nitro/blob/master/LICENSE
+
+//go:build !js && brotli_dictionary
+// +build !js,brotli_dictionary
+
+package arbcompress
+
+import (
+	"bytes"
+	"testing"
+)
+
+func TestArbCompressDictionary(t *testing.T) {
+	dictionary := []byte("This is a long and repetitive string. The quick brown fox jumped over the lazy dog.")
+	data := []byte("The quick brown fox jumped over the lazy dog. This is a long and repetitive string.")
+
+	if _, err := CompressWithDictionary(data, LEVEL_WELL, ChainDictionary); err == nil {
+		t.Fatal("compressed with an unset dictionary")
+	}
+	if err := SetDictionary(dictionary); err != nil {
+		t.Fatal(err)
+	}
+	if err := SetDictionary(dictionary); err == nil {
+		t.Fatal("dictionary set twice")
+	}
+
+	for _, dict := range []Dictionary{EmptyDictionary, ChainDictionary} {
+		compressed, err := CompressWithDictionary(data, LEVEL_WELL, dict)
+		if err != nil {
+			t.Fatal(err)
+		}
+		res, err := DecompressWithDictionary(compressed, len(data)*2+64, dict)
+		if err != nil {
+			t.Fatal(err)
+		}
+		if !bytes.Equal(res, data) {
+			t.Fatal("results differ ", res, " vs. ", data)
+		}
+	}
+
+	compressed, err := CompressWithDictionary(data, LEVEL_WELL, ChainDictionary)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if res, err := Decompress(compressed, len(data)*2+64); err == nil && bytes.Equal(res, data) {
+		t.Fatal("decompressed without the dictionary")
+	}
+	if _, err := DecompressWithDictionary(compressed, len(data)*2+64, Dictionary(2)); err == nil {
+		t.Fatal("decompressed with an unknown dictionary")
+	}
+}
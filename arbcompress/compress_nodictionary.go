@@ -0,0 +1,18 @@
+// Copyright 2024, Offchain Labs, Inc.
+// For license information, see https://github.com/nitro/blob/master/LICENSE
+
+//go:build !js && !brotli_dictionary
+// +build !js,!brotli_dictionary
+
+package arbcompress
+
+// Custom dictionaries need brotli 1.1.0 or newer, so they're left out of the default build.
+const dictionarySupported = false
+
+func decompressWithDictionary(input []byte, maxSize int, dict []byte) ([]byte, error) {
+	return nil, errDictionaryUnsupported
+}
+
+func compressWithDictionary(input []byte, level int, dict []byte) ([]byte, error) {
+	return nil, errDictionaryUnsupported
+}
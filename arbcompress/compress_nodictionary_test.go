@@ -0,0 +1,36 @@
+// Copyright 2024, Offchain Labs, Inc.
+// For license information, see https://github.com/nitro/blob/master/LICENSE
+
+//go:build !js && !brotli_dictionary
+// +build !js,!brotli_dictionary
+
+package arbcompress
+
+import (
+	"bytes"
+	"errors"
+	"testing"
+)
+
+func TestArbCompressNoDictionary(t *testing.T) {
+	data := []byte("The quick brown fox jumped over the lazy dog. This is a long and repetitive string.")
+
+	if err := SetDictionary(data); !errors.Is(err, errDictionaryUnsupported) {
+		t.Fatal("expected dictionaries to be unsupported, got", err)
+	}
+	if _, err := CompressWithDictionary(data, LEVEL_WELL, ChainDictionary); err == nil {
+		t.Fatal("compressed with an unsupported dictionary")
+	}
+
+	compressed, err := CompressWithDictionary(data, LEVEL_WELL, EmptyDictionary)
+	if err != nil {
+		t.Fatal(err)
+	}
+	res, err := DecompressWithDictionary(compressed, len(data)*2+64, EmptyDictionary)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if !bytes.Equal(res, data) {
+		t.Fatal("results differ ", res, " vs. ", data)
+	}
+}
@@ -7,6 +7,7 @@
 package arbcompress
 
 import (
+	"errors"
 	"fmt"
 )
 
@@ -14,6 +15,41 @@ func brotliCompress(inBuf []byte, outBuf []byte, level int, windowSize int) int6
 
 func brotliDecompress(inBuf []byte, outBuf []byte) int64
 
+func brotliCompressWithDictionary(inBuf []byte, outBuf []byte, level int, windowSize int, dictionary Dictionary) int64
+
+func brotliDecompressWithDictionary(inBuf []byte, outBuf []byte, dictionary Dictionary) int64
+
+func brotliSetDictionary(dictionary []byte)
+
+// SetDictionary sets the chain-specific dictionary, which may only happen once.
+// The host aborts the replay if it's set again, or if it was built without dictionary support.
+func SetDictionary(dictionary []byte) error {
+	if len(dictionary) == 0 {
+		return errors.New("empty brotli dictionary")
+	}
+	brotliSetDictionary(dictionary)
+	return nil
+}
+
+func DecompressWithDictionary(input []byte, maxSize int, dictionary Dictionary) ([]byte, error) {
+	outBuf := make([]byte, maxSize)
+	outLen := brotliDecompressWithDictionary(input, outBuf, dictionary)
+	if outLen < 0 {
+		return nil, fmt.Errorf("failed decompression")
+	}
+	return outBuf[:outLen], nil
+}
+
+func CompressWithDictionary(input []byte, level int, dictionary Dictionary) ([]byte, error) {
+	maxOutSize := compressedBufferSizeFor(len(input))
+	outBuf := make([]byte, maxOutSize)
+	outLen := brotliCompressWithDictionary(input, outBuf, level, WINDOW_SIZE, dictionary)
+	if outLen < 0 {
+		return nil, fmt.Errorf("failed compression")
+	}
+	return outBuf[:outLen], nil
+}
+
 func Decompress(input []byte, maxSize int) ([]byte, error) {
 	outBuf := make([]byte, maxSize)
 	outLen := brotliDecompress(input, outBuf)
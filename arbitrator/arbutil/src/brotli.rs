@@ -0,0 +1,377 @@
+// Copyright 2023, Offchain Labs, Inc.
+// For license information, see https://github.com/OffchainLabs/nitro/blob/master/LICENSE
+
+//! Brotli routines shared by the JIT and the WAVM brotli library, so that both
+//! machines select and apply dictionaries identically. Linking against the brotli
+//! C libraries is left to the caller.
+//!
+//! Custom dictionaries need the shared-dictionary API added in brotli 1.1.0, so
+//! they're only available with the `brotli-dictionary` feature.
+
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+
+extern "C" {
+    pub fn BrotliDecoderDecompress(
+        encoded_size: usize,
+        encoded_buffer: *const u8,
+        decoded_size: *mut usize,
+        decoded_buffer: *mut u8,
+    ) -> u32;
+
+    pub fn BrotliEncoderCompress(
+        quality: u32,
+        lgwin: u32,
+        mode: u32,
+        input_size: usize,
+        input_buffer: *const u8,
+        encoded_size: *mut usize,
+        encoded_buffer: *mut u8,
+    ) -> u32;
+}
+
+const BROTLI_MODE_GENERIC: u32 = 0;
+const BROTLI_RES_SUCCESS: u32 = 1;
+
+// These values must be kept in sync with `arbcompress/compress_common.go`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive, IntoPrimitive)]
+#[repr(u32)]
+pub enum Dictionary {
+    /// No custom dictionary; brotli's built-in one still applies.
+    Empty,
+    /// The chain-specific dictionary, which must be set before use.
+    Chain,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrotliError {
+    /// The dictionary id doesn't correspond to any known dictionary.
+    UnknownDictionary(u32),
+    /// The dictionary is known but was never set.
+    UnsetDictionary(Dictionary),
+    /// The dictionary was already set, and can't be replaced.
+    AlreadySetDictionary(Dictionary),
+    /// An empty dictionary was provided.
+    EmptyDictionary,
+    /// Custom dictionaries need the `brotli-dictionary` feature.
+    Unsupported,
+    /// Brotli failed, or the output didn't fit in the space provided.
+    Failed,
+}
+
+/// The dictionaries available to a machine, indexed by [`Dictionary`].
+#[derive(Clone, Debug, Default)]
+pub struct Dictionaries {
+    chain: Option<Vec<u8>>,
+}
+
+impl Dictionaries {
+    pub const fn new() -> Self {
+        Self { chain: None }
+    }
+
+    /// Sets the chain-specific dictionary, which may only happen once.
+    pub fn set_chain(&mut self, data: Vec<u8>) -> Result<(), BrotliError> {
+        if !cfg!(feature = "brotli-dictionary") {
+            return Err(BrotliError::Unsupported);
+        }
+        if data.is_empty() {
+            return Err(BrotliError::EmptyDictionary);
+        }
+        if self.chain.is_some() {
+            return Err(BrotliError::AlreadySetDictionary(Dictionary::Chain));
+        }
+        self.chain = Some(data);
+        Ok(())
+    }
+
+    /// Looks up a dictionary by id, with `None` meaning no custom dictionary.
+    pub fn get(&self, id: u32) -> Result<Option<&[u8]>, BrotliError> {
+        let dictionary =
+            Dictionary::try_from(id).map_err(|_| BrotliError::UnknownDictionary(id))?;
+        match dictionary {
+            Dictionary::Empty => Ok(None),
+            Dictionary::Chain => match &self.chain {
+                Some(data) => Ok(Some(data)),
+                None => Err(BrotliError::UnsetDictionary(dictionary)),
+            },
+        }
+    }
+}
+
+/// Compresses `input` into at most `max_size` bytes.
+pub fn compress(
+    input: &[u8],
+    level: u32,
+    window_size: u32,
+    dictionary: Option<&[u8]>,
+    max_size: usize,
+) -> Result<Vec<u8>, BrotliError> {
+    if let Some(dictionary) = dictionary {
+        return shared::compress(input, level, window_size, dictionary, max_size);
+    }
+    let mut output = vec![0u8; max_size];
+    let mut output_len = max_size;
+
+    let res = unsafe {
+        BrotliEncoderCompress(
+            level,
+            window_size,
+            BROTLI_MODE_GENERIC,
+            input.len(),
+            input.as_ptr(),
+            &mut output_len,
+            output.as_mut_ptr(),
+        )
+    };
+    if res != BROTLI_RES_SUCCESS || output_len > max_size {
+        return Err(BrotliError::Failed);
+    }
+    output.truncate(output_len);
+    Ok(output)
+}
+
+/// Decompresses `input` into at most `max_size` bytes.
+pub fn decompress(
+    input: &[u8],
+    dictionary: Option<&[u8]>,
+    max_size: usize,
+) -> Result<Vec<u8>, BrotliError> {
+    if let Some(dictionary) = dictionary {
+        return shared::decompress(input, dictionary, max_size);
+    }
+    let mut output = vec![0u8; max_size];
+    let mut output_len = max_size;
+
+    let res = unsafe {
+        BrotliDecoderDecompress(
+            input.len(),
+            input.as_ptr(),
+            &mut output_len,
+            output.as_mut_ptr(),
+        )
+    };
+    if res != BROTLI_RES_SUCCESS || output_len > max_size {
+        return Err(BrotliError::Failed);
+    }
+    output.truncate(output_len);
+    Ok(output)
+}
+
+/// The shared-dictionary API, which needs brotli 1.1.0 or newer.
+#[cfg(feature = "brotli-dictionary")]
+mod shared {
+    use super::{BrotliError, BROTLI_MODE_GENERIC};
+    use std::{ffi::c_void, ptr};
+
+    #[repr(C)]
+    pub struct BrotliDecoderState {
+        _private: [u8; 0],
+    }
+
+    #[repr(C)]
+    pub struct BrotliEncoderState {
+        _private: [u8; 0],
+    }
+
+    #[repr(C)]
+    pub struct BrotliEncoderPreparedDictionary {
+        _private: [u8; 0],
+    }
+
+    extern "C" {
+        pub fn BrotliDecoderCreateInstance(
+            alloc: *const c_void,
+            free: *const c_void,
+            opaque: *mut c_void,
+        ) -> *mut BrotliDecoderState;
+
+        pub fn BrotliDecoderAttachDictionary(
+            state: *mut BrotliDecoderState,
+            kind: u32,
+            data_size: usize,
+            data: *const u8,
+        ) -> u32;
+
+        pub fn BrotliDecoderDecompressStream(
+            state: *mut BrotliDecoderState,
+            available_in: *mut usize,
+            next_in: *mut *const u8,
+            available_out: *mut usize,
+            next_out: *mut *mut u8,
+            total_out: *mut usize,
+        ) -> u32;
+
+        pub fn BrotliDecoderDestroyInstance(state: *mut BrotliDecoderState);
+
+        pub fn BrotliEncoderCreateInstance(
+            alloc: *const c_void,
+            free: *const c_void,
+            opaque: *mut c_void,
+        ) -> *mut BrotliEncoderState;
+
+        pub fn BrotliEncoderSetParameter(
+            state: *mut BrotliEncoderState,
+            param: u32,
+            value: u32,
+        ) -> u32;
+
+        pub fn BrotliEncoderPrepareDictionary(
+            kind: u32,
+            data_size: usize,
+            data: *const u8,
+            quality: i32,
+            alloc: *const c_void,
+            free: *const c_void,
+            opaque: *mut c_void,
+        ) -> *mut BrotliEncoderPreparedDictionary;
+
+        pub fn BrotliEncoderAttachPreparedDictionary(
+            state: *mut BrotliEncoderState,
+            dictionary: *const BrotliEncoderPreparedDictionary,
+        ) -> u32;
+
+        pub fn BrotliEncoderCompressStream(
+            state: *mut BrotliEncoderState,
+            op: u32,
+            available_in: *mut usize,
+            next_in: *mut *const u8,
+            available_out: *mut usize,
+            next_out: *mut *mut u8,
+            total_out: *mut usize,
+        ) -> u32;
+
+        pub fn BrotliEncoderIsFinished(state: *mut BrotliEncoderState) -> u32;
+
+        pub fn BrotliEncoderDestroyPreparedDictionary(
+            dictionary: *mut BrotliEncoderPreparedDictionary,
+        );
+
+        pub fn BrotliEncoderDestroyInstance(state: *mut BrotliEncoderState);
+    }
+
+    const BROTLI_TRUE: u32 = 1;
+    const BROTLI_DECODER_RESULT_SUCCESS: u32 = 1;
+    const BROTLI_PARAM_MODE: u32 = 0;
+    const BROTLI_PARAM_QUALITY: u32 = 1;
+    const BROTLI_PARAM_LGWIN: u32 = 2;
+    const BROTLI_OPERATION_FINISH: u32 = 2;
+    const BROTLI_SHARED_DICTIONARY_RAW: u32 = 0;
+
+    pub fn compress(
+        input: &[u8],
+        level: u32,
+        window_size: u32,
+        dictionary: &[u8],
+        max_size: usize,
+    ) -> Result<Vec<u8>, BrotliError> {
+        let mut output = vec![0u8; max_size];
+        let mut output_len = max_size;
+
+        unsafe {
+            let state = BrotliEncoderCreateInstance(ptr::null(), ptr::null(), ptr::null_mut());
+            if state.is_null() {
+                return Err(BrotliError::Failed);
+            }
+            let prepared = BrotliEncoderPrepareDictionary(
+                BROTLI_SHARED_DICTIONARY_RAW,
+                dictionary.len(),
+                dictionary.as_ptr(),
+                level as i32,
+                ptr::null(),
+                ptr::null(),
+                ptr::null_mut(),
+            );
+
+            let mut in_len = input.len();
+            let mut in_ptr = input.as_ptr();
+            let mut out_ptr = output.as_mut_ptr();
+            let mut written = 0;
+
+            let success = !prepared.is_null()
+                && BrotliEncoderSetParameter(state, BROTLI_PARAM_MODE, BROTLI_MODE_GENERIC)
+                    == BROTLI_TRUE
+                && BrotliEncoderSetParameter(state, BROTLI_PARAM_QUALITY, level) == BROTLI_TRUE
+                && BrotliEncoderSetParameter(state, BROTLI_PARAM_LGWIN, window_size) == BROTLI_TRUE
+                && BrotliEncoderAttachPreparedDictionary(state, prepared) == BROTLI_TRUE
+                && BrotliEncoderCompressStream(
+                    state,
+                    BROTLI_OPERATION_FINISH,
+                    &mut in_len,
+                    &mut in_ptr,
+                    &mut output_len,
+                    &mut out_ptr,
+                    &mut written,
+                ) == BROTLI_TRUE
+                && BrotliEncoderIsFinished(state) == BROTLI_TRUE;
+
+            // the encoder may reference the dictionary, so it must go first
+            BrotliEncoderDestroyInstance(state);
+            if !prepared.is_null() {
+                BrotliEncoderDestroyPreparedDictionary(prepared);
+            }
+
+            if !success || written > max_size {
+                return Err(BrotliError::Failed);
+            }
+            output.truncate(written);
+            Ok(output)
+        }
+    }
+
+    pub fn decompress(
+        input: &[u8],
+        dictionary: &[u8],
+        max_size: usize,
+    ) -> Result<Vec<u8>, BrotliError> {
+        let mut output = vec![0u8; max_size];
+        let mut output_len = max_size;
+
+        unsafe {
+            let state = BrotliDecoderCreateInstance(ptr::null(), ptr::null(), ptr::null_mut());
+            if state.is_null() {
+                return Err(BrotliError::Failed);
+            }
+
+            let mut in_len = input.len();
+            let mut in_ptr = input.as_ptr();
+            let mut out_ptr = output.as_mut_ptr();
+            let mut written = 0;
+
+            let success = BrotliDecoderAttachDictionary(
+                state,
+                BROTLI_SHARED_DICTIONARY_RAW,
+                dictionary.len(),
+                dictionary.as_ptr(),
+            ) == BROTLI_TRUE
+                && BrotliDecoderDecompressStream(
+                    state,
+                    &mut in_len,
+                    &mut in_ptr,
+                    &mut output_len,
+                    &mut out_ptr,
+                    &mut written,
+                ) == BROTLI_DECODER_RESULT_SUCCESS;
+
+            BrotliDecoderDestroyInstance(state);
+
+            if !success || written > max_size {
+                return Err(BrotliError::Failed);
+            }
+            output.truncate(written);
+            Ok(output)
+        }
+    }
+}
+
+#[cfg(not(feature = "brotli-dictionary"))]
+mod shared {
+    use super::BrotliError;
+
+    pub fn compress(_: &[u8], _: u32, _: u32, _: &[u8], _: usize) -> Result<Vec<u8>, BrotliError> {
+        Err(BrotliError::Unsupported)
+    }
+
+    pub fn decompress(_: &[u8], _: &[u8], _: usize) -> Result<Vec<u8>, BrotliError> {
+        Err(BrotliError::Unsupported)
+    }
+}
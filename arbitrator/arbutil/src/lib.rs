@@ -1,9 +1,11 @@
 // Copyright 2022-2023, Offchain Labs, Inc.
 // For license information, see https://github.com/OffchainLabs/nitro/blob/master/LICENSE
 
+#[cfg(feature = "brotli")]
+pub mod brotli;
 pub mod color;
 pub mod format;
 mod types;
 
 pub use color::{Color, DebugColor};
-pub use types::PreimageType;
+pub use types::{Bytes20, Bytes32, ErrorCode, Overflow, PreimageType};
@@ -2,6 +2,12 @@
 // For license information, see https://github.com/OffchainLabs/nitro/blob/master/LICENSE
 
 use num_enum::{IntoPrimitive, TryFromPrimitive};
+use serde::{Deserialize, Serialize};
+use std::{
+    borrow::Borrow,
+    fmt,
+    ops::{Deref, DerefMut},
+};
 
 // These values must be kept in sync with `arbutil/preimage_type.go`,
 // and the if statement in `contracts/src/osp/OneStepProverHostIo.sol` (search for "UNKNOWN_PREIMAGE_TYPE").
@@ -14,3 +20,295 @@ pub enum PreimageType {
     Sha2_256,
     EthVersionedHash,
 }
+
+/// Classifies a failure so it can be handled without parsing the error message.
+/// Error buffers crossing process boundaries are prefixed with the big-endian code.
+// These values must be kept in sync with `validator/server_jit/jit_machine.go`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[repr(u16)]
+pub enum ErrorCode {
+    Memory = 0,
+    OutOfInk = 1,
+    OutOfStack = 2,
+    Logical = 3,
+    Internal = 4,
+    Timeout = 5,
+    Cancelled = 6,
+    ProtocolViolation = 7,
+}
+
+impl ErrorCode {
+    /// Prefixes a message with this code, as sent across process boundaries.
+    pub fn prefix(self, message: &str) -> Vec<u8> {
+        let mut data = u16::from(self).to_be_bytes().to_vec();
+        data.extend(message.as_bytes());
+        data
+    }
+}
+
+/// A 256-bit value, which orders and does arithmetic as a big-endian integer.
+/// cbindgen:field-names=[bytes]
+#[derive(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[repr(C)]
+pub struct Bytes32(pub [u8; 32]);
+
+impl Deref for Bytes32 {
+    type Target = [u8; 32];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Bytes32 {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl AsRef<[u8]> for Bytes32 {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Borrow<[u8]> for Bytes32 {
+    fn borrow(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<[u8; 32]> for Bytes32 {
+    fn from(x: [u8; 32]) -> Self {
+        Self(x)
+    }
+}
+
+impl From<u32> for Bytes32 {
+    fn from(x: u32) -> Self {
+        let mut b = [0u8; 32];
+        b[(32 - 4)..].copy_from_slice(&x.to_be_bytes());
+        Self(b)
+    }
+}
+
+impl From<u64> for Bytes32 {
+    fn from(x: u64) -> Self {
+        let mut b = [0u8; 32];
+        b[(32 - 8)..].copy_from_slice(&x.to_be_bytes());
+        Self(b)
+    }
+}
+
+impl From<usize> for Bytes32 {
+    fn from(x: usize) -> Self {
+        let mut b = [0u8; 32];
+        b[(32 - (usize::BITS as usize / 8))..].copy_from_slice(&x.to_be_bytes());
+        Self(b)
+    }
+}
+
+impl From<u128> for Bytes32 {
+    fn from(x: u128) -> Self {
+        let mut b = [0u8; 32];
+        b[(32 - 16)..].copy_from_slice(&x.to_be_bytes());
+        Self(b)
+    }
+}
+
+impl Bytes32 {
+    /// Adds `x`, returning `None` if the sum exceeds 256 bits.
+    pub fn checked_add_u64(&self, x: u64) -> Option<Self> {
+        let mut b = self.0;
+        let mut carry = x as u128;
+        for byte in b.iter_mut().rev() {
+            if carry == 0 {
+                break;
+            }
+            let sum = *byte as u128 + (carry & 0xff);
+            *byte = sum as u8;
+            carry = (carry >> 8) + (sum >> 8);
+        }
+        (carry == 0).then_some(Self(b))
+    }
+
+    /// Adds one, wrapping the maximum value around to zero.
+    pub fn wrapping_increment(&self) -> Self {
+        self.checked_add_u64(1).unwrap_or_default()
+    }
+
+    /// Converts to a u64, failing if the value doesn't fit.
+    pub fn to_u64(&self) -> Result<u64, Overflow> {
+        let (high, low) = self.0.split_at(32 - 8);
+        if high.iter().any(|&x| x != 0) {
+            return Err(Overflow(*self));
+        }
+        Ok(u64::from_be_bytes(low.try_into().unwrap()))
+    }
+}
+
+impl IntoIterator for Bytes32 {
+    type Item = u8;
+    type IntoIter = std::array::IntoIter<u8, 32>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIterator::into_iter(self.0)
+    }
+}
+
+type GenericBytes32 = digest::generic_array::GenericArray<u8, digest::generic_array::typenum::U32>;
+
+impl From<GenericBytes32> for Bytes32 {
+    fn from(x: GenericBytes32) -> Self {
+        <[u8; 32]>::from(x).into()
+    }
+}
+
+impl fmt::Display for Bytes32 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self))
+    }
+}
+
+impl fmt::Debug for Bytes32 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self))
+    }
+}
+
+/// The error returned when a [`Bytes32`] doesn't fit in a narrower integer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Overflow(pub Bytes32);
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} overflows a u64", self.0)
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+/// A 160-bit value, such as an address.
+/// cbindgen:field-names=[bytes]
+#[derive(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[repr(C)]
+pub struct Bytes20(pub [u8; 20]);
+
+impl Deref for Bytes20 {
+    type Target = [u8; 20];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Bytes20 {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl AsRef<[u8]> for Bytes20 {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Borrow<[u8]> for Bytes20 {
+    fn borrow(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<[u8; 20]> for Bytes20 {
+    fn from(x: [u8; 20]) -> Self {
+        Self(x)
+    }
+}
+
+impl IntoIterator for Bytes20 {
+    type Item = u8;
+    type IntoIter = std::array::IntoIter<u8, 20>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIterator::into_iter(self.0)
+    }
+}
+
+impl fmt::Display for Bytes20 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self))
+    }
+}
+
+impl fmt::Debug for Bytes20 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Bytes32;
+    use num_bigint::BigUint;
+    use rand::{Rng, RngCore};
+    use std::cmp::Ordering;
+
+    /// Fills a random value whose high bytes are often zero and whose low bytes are often 0xff,
+    /// so that overflow boundaries and long carry chains come up regularly.
+    fn random_bytes32(rng: &mut impl Rng) -> Bytes32 {
+        let mut value = Bytes32::default();
+        rng.fill_bytes(&mut *value);
+        let zeroes = rng.gen_range(0..=32);
+        let ones = rng.gen_range(0..=32);
+        value[..zeroes].fill(0);
+        value[(32 - ones)..].fill(0xff);
+        value
+    }
+
+    fn big(value: &Bytes32) -> BigUint {
+        BigUint::from_bytes_be(&**value)
+    }
+
+    #[test]
+    fn test_bytes32_arithmetic() {
+        let mut rng = rand::thread_rng();
+        let limit = BigUint::from(1u32) << 256;
+
+        for _ in 0..10_000 {
+            let value = random_bytes32(&mut rng);
+            let other = random_bytes32(&mut rng);
+            let addend = match rng.gen_range(0..4) {
+                0 => 0,
+                1 => 1,
+                2 => u64::MAX,
+                _ => rng.next_u64(),
+            };
+
+            let sum = big(&value) + addend;
+            match value.checked_add_u64(addend) {
+                Some(result) => assert_eq!(big(&result), sum),
+                None => assert!(sum >= limit, "{value} + {addend} shouldn't overflow"),
+            }
+
+            let increment = (big(&value) + 1u32) % &limit;
+            assert_eq!(big(&value.wrapping_increment()), increment);
+
+            let narrow = u64::try_from(&big(&value)).ok();
+            assert_eq!(value.to_u64().ok(), narrow);
+
+            // order must follow the integer value, not the low byte
+            let order: Ordering = big(&value).cmp(&big(&other));
+            assert_eq!(value.cmp(&other), order);
+
+            // conversions place the integer in the low, big-endian bytes
+            let x = rng.next_u64();
+            let y: u128 = rng.gen();
+            assert_eq!(big(&Bytes32::from(x as u32)), BigUint::from(x as u32));
+            assert_eq!(big(&Bytes32::from(x)), BigUint::from(x));
+            assert_eq!(big(&Bytes32::from(x as usize)), BigUint::from(x as usize));
+            assert_eq!(big(&Bytes32::from(y)), BigUint::from(y));
+            assert_eq!(Bytes32::from(x).to_u64(), Ok(x));
+        }
+    }
+}
@@ -1,4 +1,19 @@
 fn main() {
+    // dictionary support needs the shared-dictionary API added in brotli 1.1.0
+    if std::env::var_os("CARGO_FEATURE_BROTLI_DICTIONARY").is_some() {
+        let lib = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../target/lib/libbrotlidec-static.a"
+        );
+        println!("cargo:rerun-if-changed={lib}");
+        if let Ok(bytes) = std::fs::read(lib) {
+            let symbol = b"BrotliDecoderAttachDictionary";
+            if !bytes.windows(symbol.len()).any(|x| x == symbol) {
+                panic!("{lib} predates brotli 1.1.0, rebuild it with scripts/build-brotli.sh -l");
+            }
+        }
+    }
+
     // Tell Cargo that if the given file changes, to rerun this build script.
     println!("cargo:rustc-link-search=../target/lib/");
     println!("cargo:rustc-link-lib=static=brotlienc-static");
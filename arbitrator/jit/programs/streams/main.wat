@@ -0,0 +1,22 @@
+(module
+    (import "go" "runtime.wasmWrite" (func $wasm_write (param i32)))
+    (memory (export "mem") 1)
+
+    ;; each Go stack frame holds fd, ptr, and len in the 8-byte slots after sp
+    (data (i32.const 0x108) "\01\00\00\00\00\00\00\00\00\02\00\00\00\00\00\00\05\00\00\00")
+    (data (i32.const 0x148) "\02\00\00\00\00\00\00\00\10\02\00\00\00\00\00\00\04\00\00\00")
+    (data (i32.const 0x188) "\01\00\00\00\00\00\00\00\20\02\00\00\00\00\00\00\05\00\00\00")
+    (data (i32.const 0x1c8) "\01\00\00\00\00\00\00\00\30\02\00\00\00\00\00\00\01\00\00\00")
+    (data (i32.const 0x200) "hello")
+    (data (i32.const 0x210) "oops")
+    (data (i32.const 0x220) "world")
+    (data (i32.const 0x230) "!")
+
+    (func (export "run") (param $argc i32) (param $argv i32)
+        (call $wasm_write (i32.const 0x100))
+        (call $wasm_write (i32.const 0x140))
+        (call $wasm_write (i32.const 0x180))
+        (call $wasm_write (i32.const 0x1c0)))
+    (func (export "resume"))
+    (func (export "getsp") (result i32)
+        i32.const 0))
@@ -1,93 +1,115 @@
 // Copyright 2022, Offchain Labs, Inc.
 // For license information, see https://github.com/nitro/blob/master/LICENSE
 
-use crate::{gostack::GoStack, machine::WasmEnvMut};
-
-extern "C" {
-    pub fn BrotliDecoderDecompress(
-        encoded_size: usize,
-        encoded_buffer: *const u8,
-        decoded_size: *mut usize,
-        decoded_buffer: *mut u8,
-    ) -> u32;
-
-    pub fn BrotliEncoderCompress(
-        quality: u32,
-        lgwin: u32,
-        mode: u32,
-        input_size: usize,
-        input_buffer: *const u8,
-        encoded_size: *mut usize,
-        encoded_buffer: *mut u8,
-    ) -> u32;
-}
-
-const BROTLI_MODE_GENERIC: u32 = 0;
-const BROTLI_RES_SUCCESS: u32 = 1;
+use crate::{
+    gostack::GoStack,
+    machine::{Escape, MaybeEscape, WasmEnvMut},
+};
+use arbutil::brotli;
 
-pub fn brotli_compress(mut env: WasmEnvMut, sp: u32) {
+pub fn brotli_compress(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
     let (sp, _) = GoStack::new(sp, &mut env);
 
     //(inBuf []byte, outBuf []byte, level int, windowSize int) int
-    let in_buf_ptr = sp.read_u64(0);
-    let in_buf_len = sp.read_u64(1);
-    let out_buf_ptr = sp.read_u64(3);
-    let out_buf_len = sp.read_u64(4);
-    let level = sp.read_u64(6) as u32;
-    let windowsize = sp.read_u64(7) as u32;
+    let in_buf_ptr = sp.read_u64(0)?;
+    let in_buf_len = sp.read_u64(1)?;
+    let out_buf_ptr = sp.read_u64(3)?;
+    let out_buf_len = sp.read_u64(4)?;
+    let level = sp.read_u64(6)? as u32;
+    let windowsize = sp.read_u64(7)? as u32;
     let output_arg = 8;
 
-    let in_slice = sp.read_slice(in_buf_ptr, in_buf_len);
-    let mut output = vec![0u8; out_buf_len as usize];
-    let mut output_len = out_buf_len as usize;
-
-    let res = unsafe {
-        BrotliEncoderCompress(
-            level,
-            windowsize,
-            BROTLI_MODE_GENERIC,
-            in_buf_len as usize,
-            in_slice.as_ptr(),
-            &mut output_len,
-            output.as_mut_ptr(),
-        )
-    };
-
-    if (res != BROTLI_RES_SUCCESS) || (output_len as u64 > out_buf_len) {
-        sp.write_u64(output_arg, u64::MAX);
-        return;
+    let in_slice = sp.read_slice(in_buf_ptr, in_buf_len)?;
+    match brotli::compress(&in_slice, level, windowsize, None, out_buf_len as usize) {
+        Ok(output) => {
+            sp.write_slice(out_buf_ptr, &output)?;
+            sp.write_u64(output_arg, output.len() as u64)
+        }
+        Err(_) => sp.write_u64(output_arg, u64::MAX),
     }
-    sp.write_slice(out_buf_ptr, &output[..output_len]);
-    sp.write_u64(output_arg, output_len as u64);
 }
 
-pub fn brotli_decompress(mut env: WasmEnvMut, sp: u32) {
+pub fn brotli_decompress(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
     let (sp, _) = GoStack::new(sp, &mut env);
 
     //(inBuf []byte, outBuf []byte) int
-    let in_buf_ptr = sp.read_u64(0);
-    let in_buf_len = sp.read_u64(1);
-    let out_buf_ptr = sp.read_u64(3);
-    let out_buf_len = sp.read_u64(4);
+    let in_buf_ptr = sp.read_u64(0)?;
+    let in_buf_len = sp.read_u64(1)?;
+    let out_buf_ptr = sp.read_u64(3)?;
+    let out_buf_len = sp.read_u64(4)?;
     let output_arg = 6;
 
-    let in_slice = sp.read_slice(in_buf_ptr, in_buf_len);
-    let mut output = vec![0u8; out_buf_len as usize];
-    let mut output_len = out_buf_len as usize;
-
-    let res = unsafe {
-        BrotliDecoderDecompress(
-            in_buf_len as usize,
-            in_slice.as_ptr(),
-            &mut output_len,
-            output.as_mut_ptr(),
-        )
-    };
-
-    if (res != BROTLI_RES_SUCCESS) || (output_len as u64 > out_buf_len) {
-        sp.write_u64(output_arg, u64::MAX);
-        return;
+    let in_slice = sp.read_slice(in_buf_ptr, in_buf_len)?;
+    match brotli::decompress(&in_slice, None, out_buf_len as usize) {
+        Ok(output) => {
+            sp.write_slice(out_buf_ptr, &output)?;
+            sp.write_u64(output_arg, output.len() as u64)
+        }
+        Err(_) => sp.write_u64(output_arg, u64::MAX),
+    }
+}
+
+pub fn brotli_compress_with_dictionary(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
+    let (sp, env) = GoStack::new(sp, &mut env);
+
+    //(inBuf []byte, outBuf []byte, level int, windowSize int, dictionary uint32) int
+    let in_buf_ptr = sp.read_u64(0)?;
+    let in_buf_len = sp.read_u64(1)?;
+    let out_buf_ptr = sp.read_u64(3)?;
+    let out_buf_len = sp.read_u64(4)?;
+    let level = sp.read_u64(6)? as u32;
+    let windowsize = sp.read_u64(7)? as u32;
+    let dictionary = sp.read_u32(8)?;
+    let output_arg = 9;
+
+    let in_slice = sp.read_slice(in_buf_ptr, in_buf_len)?;
+    let output = env.brotli_dictionaries.get(dictionary).and_then(|dict| {
+        brotli::compress(&in_slice, level, windowsize, dict, out_buf_len as usize)
+    });
+    match output {
+        Ok(output) => {
+            sp.write_slice(out_buf_ptr, &output)?;
+            sp.write_u64(output_arg, output.len() as u64)
+        }
+        Err(_) => sp.write_u64(output_arg, u64::MAX),
+    }
+}
+
+pub fn brotli_decompress_with_dictionary(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
+    let (sp, env) = GoStack::new(sp, &mut env);
+
+    //(inBuf []byte, outBuf []byte, dictionary uint32) int
+    let in_buf_ptr = sp.read_u64(0)?;
+    let in_buf_len = sp.read_u64(1)?;
+    let out_buf_ptr = sp.read_u64(3)?;
+    let out_buf_len = sp.read_u64(4)?;
+    let dictionary = sp.read_u32(6)?;
+    let output_arg = 7;
+
+    let in_slice = sp.read_slice(in_buf_ptr, in_buf_len)?;
+    let output = env
+        .brotli_dictionaries
+        .get(dictionary)
+        .and_then(|dict| brotli::decompress(&in_slice, dict, out_buf_len as usize));
+    match output {
+        Ok(output) => {
+            sp.write_slice(out_buf_ptr, &output)?;
+            sp.write_u64(output_arg, output.len() as u64)
+        }
+        Err(_) => sp.write_u64(output_arg, u64::MAX),
+    }
+}
+
+pub fn brotli_set_dictionary(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
+    let (sp, env) = GoStack::new(sp, &mut env);
+
+    //(dictionary []byte)
+    let dict_ptr = sp.read_u64(0)?;
+    let dict_len = sp.read_u64(1)?;
+
+    let dictionary = sp.read_slice(dict_ptr, dict_len)?;
+    if let Err(err) = env.brotli_dictionaries.set_chain(dictionary) {
+        return Escape::hostio(format!("failed to set brotli dictionary: {err:?}"));
     }
-    sp.write_slice(out_buf_ptr, &output[..output_len]);
-    sp.write_u64(output_arg, output_len as u64);
+    Ok(())
 }
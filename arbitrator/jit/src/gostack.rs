@@ -4,7 +4,7 @@
 #![allow(clippy::useless_transmute)]
 
 use crate::{
-    machine::{WasmEnv, WasmEnvMut},
+    machine::{Escape, MaybeEscape, WasmEnv, WasmEnvMut},
     syscall::JsValue,
 };
 
@@ -12,7 +12,10 @@ use ouroboros::self_referencing;
 use rand_pcg::Pcg32;
 use wasmer::{AsStoreRef, Memory, MemoryView, StoreRef, WasmPtr};
 
-use std::collections::{BTreeSet, BinaryHeap};
+use std::{
+    collections::{BTreeSet, BinaryHeap},
+    io::Write,
+};
 
 #[self_referencing]
 struct MemoryViewContainer {
@@ -83,84 +86,108 @@ impl GoStack {
     }
 
     fn offset(&self, arg: u32) -> u32 {
-        self.start + self.relative_offset(arg)
+        self.start.saturating_add(self.relative_offset(arg))
+    }
+
+    /// Describes an access of `len` bytes at `ptr` that doesn't fit within Go's memory.
+    pub fn out_of_bounds(&self, ptr: u64, len: u64) -> Escape {
+        let size = self.memory_size();
+        Escape::Memory(format!(
+            "{len} bytes at {ptr:#x} exceed {size} bytes of memory"
+        ))
     }
 
-    pub fn read_u8(&self, arg: u32) -> u8 {
+    /// Ensures `len` bytes at `ptr` lie within Go's memory.
+    fn check_bounds(&self, ptr: u64, len: u64) -> MaybeEscape {
+        match ptr.checked_add(len) {
+            Some(end) if u32::try_from(ptr).is_ok() && end <= self.memory_size() => Ok(()),
+            _ => Err(self.out_of_bounds(ptr, len)),
+        }
+    }
+
+    pub fn read_u8(&self, arg: u32) -> Result<u8, Escape> {
         self.read_u8_ptr(self.offset(arg))
     }
 
-    pub fn read_u32(&self, arg: u32) -> u32 {
+    pub fn read_u32(&self, arg: u32) -> Result<u32, Escape> {
         self.read_u32_ptr(self.offset(arg))
     }
 
-    pub fn read_u64(&self, arg: u32) -> u64 {
+    pub fn read_u64(&self, arg: u32) -> Result<u64, Escape> {
         self.read_u64_ptr(self.offset(arg))
     }
 
-    pub fn read_u8_ptr(&self, ptr: u32) -> u8 {
-        let ptr: WasmPtr<u8> = WasmPtr::new(ptr);
-        ptr.deref(self.view()).read().unwrap()
+    pub fn read_u8_ptr(&self, ptr: u32) -> Result<u8, Escape> {
+        let wasm: WasmPtr<u8> = WasmPtr::new(ptr);
+        let read = wasm.deref(self.view()).read();
+        read.map_err(|_| self.out_of_bounds(ptr.into(), 1))
     }
 
-    pub fn read_u32_ptr(&self, ptr: u32) -> u32 {
-        let ptr: WasmPtr<u32> = WasmPtr::new(ptr);
-        ptr.deref(self.view()).read().unwrap()
+    pub fn read_u32_ptr(&self, ptr: u32) -> Result<u32, Escape> {
+        let wasm: WasmPtr<u32> = WasmPtr::new(ptr);
+        let read = wasm.deref(self.view()).read();
+        read.map_err(|_| self.out_of_bounds(ptr.into(), 4))
     }
 
-    pub fn read_u64_ptr(&self, ptr: u32) -> u64 {
-        let ptr: WasmPtr<u64> = WasmPtr::new(ptr);
-        ptr.deref(self.view()).read().unwrap()
+    pub fn read_u64_ptr(&self, ptr: u32) -> Result<u64, Escape> {
+        let wasm: WasmPtr<u64> = WasmPtr::new(ptr);
+        let read = wasm.deref(self.view()).read();
+        read.map_err(|_| self.out_of_bounds(ptr.into(), 8))
     }
 
-    pub fn write_u8(&self, arg: u32, x: u8) {
-        self.write_u8_ptr(self.offset(arg), x);
+    pub fn write_u8(&self, arg: u32, x: u8) -> MaybeEscape {
+        self.write_u8_ptr(self.offset(arg), x)
     }
 
-    pub fn write_u32(&self, arg: u32, x: u32) {
-        self.write_u32_ptr(self.offset(arg), x);
+    pub fn write_u32(&self, arg: u32, x: u32) -> MaybeEscape {
+        self.write_u32_ptr(self.offset(arg), x)
     }
 
-    pub fn write_u64(&self, arg: u32, x: u64) {
-        self.write_u64_ptr(self.offset(arg), x);
+    pub fn write_u64(&self, arg: u32, x: u64) -> MaybeEscape {
+        self.write_u64_ptr(self.offset(arg), x)
     }
 
-    pub fn write_u8_ptr(&self, ptr: u32, x: u8) {
-        let ptr: WasmPtr<u8> = WasmPtr::new(ptr);
-        ptr.deref(self.view()).write(x).unwrap();
+    pub fn write_u8_ptr(&self, ptr: u32, x: u8) -> MaybeEscape {
+        let wasm: WasmPtr<u8> = WasmPtr::new(ptr);
+        let write = wasm.deref(self.view()).write(x);
+        write.map_err(|_| self.out_of_bounds(ptr.into(), 1))
     }
 
-    pub fn write_u32_ptr(&self, ptr: u32, x: u32) {
-        let ptr: WasmPtr<u32> = WasmPtr::new(ptr);
-        ptr.deref(self.view()).write(x).unwrap();
+    pub fn write_u32_ptr(&self, ptr: u32, x: u32) -> MaybeEscape {
+        let wasm: WasmPtr<u32> = WasmPtr::new(ptr);
+        let write = wasm.deref(self.view()).write(x);
+        write.map_err(|_| self.out_of_bounds(ptr.into(), 4))
     }
 
-    pub fn write_u64_ptr(&self, ptr: u32, x: u64) {
-        let ptr: WasmPtr<u64> = WasmPtr::new(ptr);
-        ptr.deref(self.view()).write(x).unwrap();
+    pub fn write_u64_ptr(&self, ptr: u32, x: u64) -> MaybeEscape {
+        let wasm: WasmPtr<u64> = WasmPtr::new(ptr);
+        let write = wasm.deref(self.view()).write(x);
+        write.map_err(|_| self.out_of_bounds(ptr.into(), 8))
     }
 
-    pub fn read_slice(&self, ptr: u64, len: u64) -> Vec<u8> {
-        u32::try_from(ptr).expect("Go pointer not a u32"); // kept for consistency
-        let len = u32::try_from(len).expect("length isn't a u32") as usize;
-        let mut data = vec![0; len];
-        self.view().read(ptr, &mut data).expect("failed to read");
-        data
+    pub fn read_slice(&self, ptr: u64, len: u64) -> Result<Vec<u8>, Escape> {
+        self.check_bounds(ptr, len)?; // before allocating, in case len is corrupt
+        let mut data = vec![0; len as usize];
+        let read = self.view().read(ptr, &mut data);
+        read.map_err(|_| self.out_of_bounds(ptr, len))?;
+        Ok(data)
     }
 
-    pub fn write_slice(&self, ptr: u64, src: &[u8]) {
-        u32::try_from(ptr).expect("Go pointer not a u32");
-        self.view().write(ptr, src).unwrap();
+    pub fn write_slice(&self, ptr: u64, src: &[u8]) -> MaybeEscape {
+        let len = src.len() as u64;
+        self.check_bounds(ptr, len)?;
+        let write = self.view().write(ptr, src);
+        write.map_err(|_| self.out_of_bounds(ptr, len))
     }
 
-    pub fn read_value_slice(&self, mut ptr: u64, len: u64) -> Vec<JsValue> {
+    pub fn read_value_slice(&self, mut ptr: u64, len: u64) -> Result<Vec<JsValue>, Escape> {
         let mut values = Vec::new();
         for _ in 0..len {
-            let p = u32::try_from(ptr).expect("Go pointer not a u32");
-            values.push(JsValue::new(self.read_u64_ptr(p)));
+            let p = u32::try_from(ptr).map_err(|_| self.out_of_bounds(ptr, 8))?;
+            values.push(JsValue::new(self.read_u64_ptr(p)?));
             ptr += 8;
         }
-        values
+        Ok(values)
     }
 }
 
@@ -173,17 +200,47 @@ pub struct GoRuntimeState {
     pub timeouts: TimeoutState,
     /// Deterministic source of random data
     pub rng: Pcg32,
+    /// When capturing, what Go wrote to stdout (fd 1) and stderr (fd 2), in order
+    pub output: Option<Vec<(u32, Vec<u8>)>>,
 }
 
 impl Default for GoRuntimeState {
     fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+impl GoRuntimeState {
+    pub fn new(capture_output: bool) -> Self {
         Self {
             time: 0,
             time_interval: 10_000_000,
             timeouts: TimeoutState::default(),
             rng: Pcg32::new(0xcafef00dd15ea5e5, 0xa02bdbf7bb3c0a7),
+            output: capture_output.then(Vec::new),
         }
     }
+
+    /// Writes to stderr when `fd` is 2 and to stdout otherwise, or to the capture buffer if enabled.
+    pub fn write_output(&mut self, fd: u64, data: &[u8]) {
+        let fd: u32 = if fd == 2 { 2 } else { 1 };
+        let Some(output) = &mut self.output else {
+            match fd {
+                2 => std::io::stderr().lock().write_all(data).unwrap(),
+                _ => std::io::stdout().lock().write_all(data).unwrap(),
+            }
+            return;
+        };
+        match output.last_mut() {
+            Some((last, text)) if *last == fd => text.extend(data),
+            _ => output.push((fd, data.to_vec())),
+        }
+    }
+
+    /// Takes the output captured so far, merging consecutive writes to the same stream.
+    pub fn drain_output(&mut self) -> Vec<(u32, Vec<u8>)> {
+        self.output.as_mut().map(std::mem::take).unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -3,10 +3,10 @@
 
 use crate::{
     arbcompress, gostack::GoRuntimeState, runtime, socket, syscall, syscall::JsRuntimeState,
-    wavmio, wavmio::Bytes32, Opts,
+    wavmio, Opts,
 };
 
-use arbutil::{Color, PreimageType};
+use arbutil::{brotli::Dictionaries, Bytes32, Color, ErrorCode, PreimageType};
 use eyre::{bail, Result, WrapErr};
 use sha3::{Digest, Keccak256};
 use thiserror::Error;
@@ -118,6 +118,9 @@ pub fn create(opts: &Opts, env: WasmEnv) -> (Instance, FunctionEnv<WasmEnv>, Sto
 
             "github.com/offchainlabs/nitro/arbcompress.brotliCompress" => func!(arbcompress::brotli_compress),
             "github.com/offchainlabs/nitro/arbcompress.brotliDecompress" => func!(arbcompress::brotli_decompress),
+            "github.com/offchainlabs/nitro/arbcompress.brotliCompressWithDictionary" => func!(arbcompress::brotli_compress_with_dictionary),
+            "github.com/offchainlabs/nitro/arbcompress.brotliDecompressWithDictionary" => func!(arbcompress::brotli_decompress_with_dictionary),
+            "github.com/offchainlabs/nitro/arbcompress.brotliSetDictionary" => func!(arbcompress::brotli_set_dictionary),
         },
     };
 
@@ -153,6 +156,8 @@ pub enum Escape {
     Failure(String),
     #[error("hostio failed with `{0}`")]
     HostIO(String),
+    #[error("memory access failed with `{0}`")]
+    Memory(String),
     #[error("hostio socket failed with `{0}`")]
     SocketError(#[from] io::Error),
 }
@@ -173,6 +178,17 @@ impl Escape {
     }
 }
 
+impl From<&Escape> for ErrorCode {
+    fn from(escape: &Escape) -> Self {
+        match escape {
+            Escape::Exit(_) | Escape::HostIO(_) => ErrorCode::Logical,
+            Escape::Failure(_) => ErrorCode::Internal,
+            Escape::Memory(_) => ErrorCode::Memory,
+            Escape::SocketError(_) => ErrorCode::ProtocolViolation,
+        }
+    }
+}
+
 impl From<RuntimeError> for Escape {
     fn from(outcome: RuntimeError) -> Self {
         match outcome.downcast() {
@@ -184,7 +200,7 @@ impl From<RuntimeError> for Escape {
 
 pub type WasmEnvMut<'a> = FunctionEnvMut<'a, WasmEnv>;
 pub type Inbox = BTreeMap<u64, Vec<u8>>;
-pub type Preimages = BTreeMap<PreimageType, BTreeMap<[u8; 32], Vec<u8>>>;
+pub type Preimages = BTreeMap<PreimageType, BTreeMap<Bytes32, Vec<u8>>>;
 
 #[derive(Default)]
 pub struct WasmEnv {
@@ -204,6 +220,8 @@ pub struct WasmEnv {
     pub sequencer_messages: Inbox,
     /// The delayed inbox's messages
     pub delayed_messages: Inbox,
+    /// The dictionaries available to brotli
+    pub brotli_dictionaries: Dictionaries,
     /// The purpose and connections of this process
     pub process: ProcessEnv,
     /// The exported funcs callable in hostio
@@ -215,6 +233,9 @@ impl WasmEnv {
         let mut env = WasmEnv::default();
         env.process.forks = opts.forks;
         env.process.debug = opts.debug;
+        if opts.record_js_fields.is_some() {
+            env.js_state.accessed_fields = Some(vec![]);
+        }
 
         let mut inbox_position = opts.inbox_position;
         let mut delayed_position = opts.delayed_inbox_position;
@@ -265,7 +286,7 @@ impl WasmEnv {
                         arg = &arg[2..];
                     }
                     let mut bytes32 = Bytes32::default();
-                    hex::decode_to_slice(arg, &mut bytes32)
+                    hex::decode_to_slice(arg, &mut *bytes32)
                         .wrap_err_with(|| format!("failed to parse {} contents", name))?;
                     Ok(bytes32)
                 }
@@ -280,35 +301,37 @@ impl WasmEnv {
         Ok(env)
     }
 
-    pub fn send_results(&mut self, error: Option<String>, memory_used: u64) {
-        let writer = match &mut self.process.socket {
-            Some((writer, _)) => writer,
-            None => return,
+    pub fn send_results(&mut self, error: Option<(ErrorCode, String)>, memory_used: u64) {
+        let Some((mut writer, reader)) = self.process.socket.take() else {
+            return;
         };
-
-        macro_rules! check {
-            ($expr:expr) => {{
-                if let Err(comms_error) = $expr {
-                    eprintln!("Failed to send results to Go: {comms_error}");
-                    panic!("Communication failure");
-                }
-            }};
+        if let Err(comms_error) = self.write_results(&mut writer, error, memory_used) {
+            eprintln!("Failed to send results to Go: {comms_error}");
+            panic!("Communication failure");
         }
+        self.process.socket = Some((writer, reader));
+    }
 
-        if let Some(error) = error {
-            check!(socket::write_u8(writer, socket::FAILURE));
-            check!(socket::write_bytes(writer, &error.into_bytes()));
-            check!(writer.flush());
-            return;
+    /// Writes the outcome in the format `validator/server_jit/jit_machine.go` reads.
+    pub fn write_results<T: Write>(
+        &self,
+        writer: &mut BufWriter<T>,
+        error: Option<(ErrorCode, String)>,
+        memory_used: u64,
+    ) -> io::Result<()> {
+        if let Some((code, error)) = error {
+            socket::write_u8(writer, socket::FAILURE)?;
+            socket::write_bytes(writer, &code.prefix(&error))?;
+            return writer.flush();
         }
 
-        check!(socket::write_u8(writer, socket::SUCCESS));
-        check!(socket::write_u64(writer, self.small_globals[0]));
-        check!(socket::write_u64(writer, self.small_globals[1]));
-        check!(socket::write_bytes32(writer, &self.large_globals[0]));
-        check!(socket::write_bytes32(writer, &self.large_globals[1]));
-        check!(socket::write_u64(writer, memory_used));
-        check!(writer.flush());
+        socket::write_u8(writer, socket::SUCCESS)?;
+        socket::write_u64(writer, self.small_globals[0])?;
+        socket::write_u64(writer, self.small_globals[1])?;
+        socket::write_bytes32(writer, &self.large_globals[0])?;
+        socket::write_bytes32(writer, &self.large_globals[1])?;
+        socket::write_u64(writer, memory_used)?;
+        writer.flush()
     }
 }
 
@@ -3,7 +3,7 @@
 
 use crate::machine::{Escape, WasmEnv};
 
-use arbutil::{color, Color};
+use arbutil::{color, Color, ErrorCode};
 use structopt::StructOpt;
 use wasmer::Value;
 
@@ -45,6 +45,8 @@ pub struct Opts {
     forks: bool,
     #[structopt(long)]
     debug: bool,
+    #[structopt(long)]
+    record_js_fields: Option<PathBuf>,
 }
 
 fn main() {
@@ -97,22 +99,36 @@ fn main() {
     let time = format!("{}ms", env.process.timestamp.elapsed().as_millis());
     let time = color::when(user, time, color::PINK);
     let hash = color::when(user, hex::encode(env.large_globals[0]), color::PINK);
+    let code = escape.as_ref().map_or(ErrorCode::Internal, ErrorCode::from);
     let (success, message) = match escape {
         Some(Escape::Exit(0)) => (true, format!("Completed in {time} with hash {hash}.")),
         Some(Escape::Exit(x)) => (false, format!("Failed in {time} with exit code {x}.")),
         Some(Escape::Failure(err)) => (false, format!("Jit failed with {err} in {time}.")),
         Some(Escape::HostIO(err)) => (false, format!("Hostio failed with {err} in {time}.")),
+        Some(Escape::Memory(err)) => (false, format!("Memory access failed with {err} in {time}.")),
         Some(Escape::SocketError(err)) => (false, format!("Socket failed with {err} in {time}.")),
         None => (false, "Machine exited prematurely".to_owned()),
     };
 
     if opts.debug {
         println!("{message}");
+        for (source, field) in &env.js_state.unknown_fields {
+            println!(
+                "Go accessed unknown JS value {source} field {}",
+                field.red()
+            );
+        }
+    }
+
+    if let (Some(path), Some(fields)) = (&opts.record_js_fields, &env.js_state.accessed_fields) {
+        if let Err(err) = syscall::write_js_fields(path, fields) {
+            eprintln!("Failed to record JS fields to {}: {err}", path.display());
+        }
     }
 
     let error = match success {
         true => None,
-        false => Some(message),
+        false => Some((code, message)),
     };
     let memory_used = memory.size().0 as u64 * 65_536;
 
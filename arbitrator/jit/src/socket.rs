@@ -4,10 +4,9 @@
 use std::{
     io,
     io::{BufReader, BufWriter, Read, Write},
-    net::TcpStream,
 };
 
-use crate::wavmio::Bytes32;
+use arbutil::Bytes32;
 
 pub const SUCCESS: u8 = 0x0;
 pub const FAILURE: u8 = 0x1;
@@ -26,7 +25,7 @@ pub fn read_u64<T: Read>(reader: &mut BufReader<T>) -> Result<u64, io::Error> {
 
 pub fn read_bytes32<T: Read>(reader: &mut BufReader<T>) -> Result<Bytes32, io::Error> {
     let mut buf = Bytes32::default();
-    reader.read_exact(&mut buf).map(|_| buf)
+    reader.read_exact(&mut *buf).map(|_| buf)
 }
 
 pub fn read_bytes<T: Read>(reader: &mut BufReader<T>) -> Result<Vec<u8>, io::Error> {
@@ -36,21 +35,21 @@ pub fn read_bytes<T: Read>(reader: &mut BufReader<T>) -> Result<Vec<u8>, io::Err
     Ok(buf)
 }
 
-pub fn write_u8(writer: &mut BufWriter<TcpStream>, data: u8) -> Result<(), io::Error> {
+pub fn write_u8<T: Write>(writer: &mut BufWriter<T>, data: u8) -> Result<(), io::Error> {
     let buf = [data; 1];
     writer.write_all(&buf)
 }
 
-pub fn write_u64(writer: &mut BufWriter<TcpStream>, data: u64) -> Result<(), io::Error> {
+pub fn write_u64<T: Write>(writer: &mut BufWriter<T>, data: u64) -> Result<(), io::Error> {
     let buf = data.to_be_bytes();
     writer.write_all(&buf)
 }
 
-pub fn write_bytes32(writer: &mut BufWriter<TcpStream>, data: &Bytes32) -> Result<(), io::Error> {
-    writer.write_all(data)
+pub fn write_bytes32<T: Write>(writer: &mut BufWriter<T>, data: &Bytes32) -> Result<(), io::Error> {
+    writer.write_all(&**data)
 }
 
-pub fn write_bytes(writer: &mut BufWriter<TcpStream>, data: &[u8]) -> Result<(), io::Error> {
+pub fn write_bytes<T: Write>(writer: &mut BufWriter<T>, data: &[u8]) -> Result<(), io::Error> {
     write_u64(writer, data.len() as u64)?;
     writer.write_all(data)
 }
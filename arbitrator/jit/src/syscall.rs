@@ -10,20 +10,23 @@ use arbutil::Color;
 use rand::RngCore;
 use wasmer::AsStoreMut;
 
-use std::{collections::BTreeMap, io::Write};
+use std::{collections::BTreeMap, fs, io, path::Path};
 
 const ZERO_ID: u32 = 1;
 const NULL_ID: u32 = 2;
-const GLOBAL_ID: u32 = 5;
-const GO_ID: u32 = 6;
+pub const GLOBAL_ID: u32 = 5;
+pub const GO_ID: u32 = 6;
 
 const OBJECT_ID: u32 = 100;
 const ARRAY_ID: u32 = 101;
 const PROCESS_ID: u32 = 102;
-const FS_ID: u32 = 103;
-const UINT8_ARRAY_ID: u32 = 104;
+pub const FS_ID: u32 = 103;
+pub const UINT8_ARRAY_ID: u32 = 104;
 const CRYPTO_ID: u32 = 105;
 const DATE_ID: u32 = 106;
+const TEXT_ENCODER_ID: u32 = 107;
+const TEXT_DECODER_ID: u32 = 108;
+const PERFORMANCE_ID: u32 = 109;
 
 const FS_CONSTANTS_ID: u32 = 200;
 
@@ -35,6 +38,45 @@ pub struct JsRuntimeState {
     pool: DynamicObjectPool,
     /// The event Go will execute next
     pub pending_event: Option<PendingEvent>,
+    /// The distinct (source, field) pairs Go accessed without a shim, in order of first access
+    pub unknown_fields: Vec<(u32, String)>,
+    /// The distinct (source, field) pairs Go accessed on static values, when recording
+    pub accessed_fields: Option<Vec<(u32, String)>>,
+}
+
+impl JsRuntimeState {
+    fn record_unknown_field(&mut self, source: u32, field: String) {
+        record_field(&mut self.unknown_fields, source, field);
+    }
+
+    fn record_accessed_field(&mut self, source: u32, field: &[u8]) {
+        if let Some(fields) = &mut self.accessed_fields {
+            let field = String::from_utf8_lossy(field);
+            record_field(fields, source, field.into());
+        }
+    }
+}
+
+fn record_field(fields: &mut Vec<(u32, String)>, source: u32, field: String) {
+    if !fields.iter().any(|x| x.0 == source && x.1 == field) {
+        fields.push((source, field));
+    }
+}
+
+/// The comment lines heading a JS field trace, which its readers skip.
+const JS_FIELDS_HEADER: &str = "\
+# The JS fields Go accessed through syscall/js, as \"<source id> <field>\" in order of first access.
+# Ids: 5 = globalThis, 6 = go, 103 = fs, 200 = fs.constants
+# Regenerate by running the replay binary under `jit --record-js-fields <path>`.
+";
+
+/// Writes the fields recorded by `--record-js-fields` in the format the shim tests expect.
+pub fn write_js_fields(path: &Path, fields: &[(u32, String)]) -> io::Result<()> {
+    let mut trace = JS_FIELDS_HEADER.to_owned();
+    for (source, field) in fields {
+        trace += &format!("{source} {field}\n");
+    }
+    fs::write(path, trace)
 }
 
 #[derive(Clone, Default, Debug)]
@@ -77,6 +119,8 @@ enum DynamicObject {
     PendingEvent(PendingEvent),
     ValueArray(Vec<GoValue>),
     Date,
+    TextEncoder,
+    TextDecoder,
 }
 
 #[derive(Clone, Debug)]
@@ -135,7 +179,7 @@ pub enum GoValue {
 }
 
 impl GoValue {
-    fn encode(self) -> u64 {
+    pub fn encode(self) -> u64 {
         let (ty, id): (u32, u32) = match self {
             GoValue::Undefined => return 0,
             GoValue::Number(mut f) => {
@@ -162,11 +206,11 @@ impl GoValue {
     }
 }
 
-fn get_field(env: &mut WasmEnv, source: u32, field: &[u8]) -> GoValue {
+pub fn get_field(env: &mut WasmEnv, source: u32, field: &[u8]) -> GoValue {
     use DynamicObject::*;
 
-    if let Some(source) = env.js_state.pool.get(source) {
-        return match (source, field) {
+    if let Some(object) = env.js_state.pool.get(source) {
+        return match (object, field) {
             (PendingEvent(event), b"id" | b"this") => event.id.assume_num_or_object(),
             (PendingEvent(event), b"args") => {
                 let args = ValueArray(event.args.clone());
@@ -177,13 +221,16 @@ fn get_field(env: &mut WasmEnv, source: u32, field: &[u8]) -> GoValue {
                 let field = String::from_utf8_lossy(field);
                 eprintln!(
                     "Go trying to access unimplemented unknown JS value {:?} field {field}",
-                    source
+                    object
                 );
+                env.js_state.record_unknown_field(source, field.into());
                 GoValue::Undefined
             }
         };
     }
 
+    env.js_state.record_accessed_field(source, field);
+
     match (source, field) {
         (GLOBAL_ID, b"Object") => GoValue::Function(OBJECT_ID),
         (GLOBAL_ID, b"Array") => GoValue::Function(ARRAY_ID),
@@ -192,6 +239,9 @@ fn get_field(env: &mut WasmEnv, source: u32, field: &[u8]) -> GoValue {
         (GLOBAL_ID, b"Uint8Array") => GoValue::Function(UINT8_ARRAY_ID),
         (GLOBAL_ID, b"crypto") => GoValue::Object(CRYPTO_ID),
         (GLOBAL_ID, b"Date") => GoValue::Object(DATE_ID),
+        (GLOBAL_ID, b"TextEncoder") => GoValue::Function(TEXT_ENCODER_ID),
+        (GLOBAL_ID, b"TextDecoder") => GoValue::Function(TEXT_DECODER_ID),
+        (GLOBAL_ID, b"performance") => GoValue::Object(PERFORMANCE_ID),
         (GLOBAL_ID, b"fetch") => GoValue::Undefined, // Triggers a code path in Go for a fake network impl
         (FS_ID, b"constants") => GoValue::Object(FS_CONSTANTS_ID),
         (
@@ -209,16 +259,17 @@ fn get_field(env: &mut WasmEnv, source: u32, field: &[u8]) -> GoValue {
         _ => {
             let field = String::from_utf8_lossy(field);
             eprintln!("Go trying to access unimplemented unknown JS value {source} field {field}");
+            env.js_state.record_unknown_field(source, field.into());
             GoValue::Undefined
         }
     }
 }
 
-pub fn js_finalize_ref(mut env: WasmEnvMut, sp: u32) {
+pub fn js_finalize_ref(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
     let (sp, env) = GoStack::new(sp, &mut env);
     let pool = &mut env.js_state.pool;
 
-    let val = JsValue::new(sp.read_u64(0));
+    let val = JsValue::new(sp.read_u64(0)?);
     match val {
         JsValue::Ref(x) if x < DYNAMIC_OBJECT_ID_BASE => {}
         JsValue::Ref(x) => {
@@ -228,14 +279,15 @@ pub fn js_finalize_ref(mut env: WasmEnvMut, sp: u32) {
         }
         val => eprintln!("Go trying to finalize {:?}", val),
     }
+    Ok(())
 }
 
-pub fn js_value_get(mut env: WasmEnvMut, sp: u32) {
+pub fn js_value_get(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
     let (sp, env) = GoStack::new(sp, &mut env);
-    let source = JsValue::new(sp.read_u64(0));
-    let field_ptr = sp.read_u64(1);
-    let field_len = sp.read_u64(2);
-    let field = sp.read_slice(field_ptr, field_len);
+    let source = JsValue::new(sp.read_u64(0)?);
+    let field_ptr = sp.read_u64(1)?;
+    let field_len = sp.read_u64(2)?;
+    let field = sp.read_slice(field_ptr, field_len)?;
     let value = match source {
         JsValue::Ref(id) => get_field(env, id, &field),
         val => {
@@ -244,27 +296,27 @@ pub fn js_value_get(mut env: WasmEnvMut, sp: u32) {
             GoValue::Null
         }
     };
-    sp.write_u64(3, value.encode());
+    sp.write_u64(3, value.encode())
 }
 
-pub fn js_value_set(mut env: WasmEnvMut, sp: u32) {
+pub fn js_value_set(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
     let (sp, env) = GoStack::new(sp, &mut env);
     use JsValue::*;
 
-    let source = JsValue::new(sp.read_u64(0));
-    let field_ptr = sp.read_u64(1);
-    let field_len = sp.read_u64(2);
-    let new_value = JsValue::new(sp.read_u64(3));
-    let field = sp.read_slice(field_ptr, field_len);
+    let source = JsValue::new(sp.read_u64(0)?);
+    let field_ptr = sp.read_u64(1)?;
+    let field_len = sp.read_u64(2)?;
+    let new_value = JsValue::new(sp.read_u64(3)?);
+    let field = sp.read_slice(field_ptr, field_len)?;
     if source == Ref(GO_ID) && &field == b"_pendingEvent" && new_value == Ref(NULL_ID) {
         env.js_state.pending_event = None;
-        return;
+        return Ok(());
     }
     if let Ref(id) = source {
         let source = env.js_state.pool.get(id);
         if let Some(DynamicObject::PendingEvent(_)) = source {
             if field == b"result" {
-                return;
+                return Ok(());
             }
         }
     }
@@ -273,9 +325,10 @@ pub fn js_value_set(mut env: WasmEnvMut, sp: u32) {
         "Go attempted to set unsupported value {:?} field {field} to {:?}",
         source, new_value,
     );
+    Ok(())
 }
 
-pub fn js_value_index(mut env: WasmEnvMut, sp: u32) {
+pub fn js_value_index(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
     let (sp, env) = GoStack::new(sp, &mut env);
 
     macro_rules! fail {
@@ -285,11 +338,11 @@ pub fn js_value_index(mut env: WasmEnvMut, sp: u32) {
         }};
     }
 
-    let source = match JsValue::new(sp.read_u64(0)) {
+    let source = match JsValue::new(sp.read_u64(0)?) {
         JsValue::Ref(x) => env.js_state.pool.get(x),
         val => fail!("Go attempted to index into {:?}", val),
     };
-    let index = match u32::try_from(sp.read_u64(1)) {
+    let index = match u32::try_from(sp.read_u64(1)?) {
         Ok(index) => index as usize,
         Err(err) => fail!("{:?}", err),
     };
@@ -301,7 +354,7 @@ pub fn js_value_index(mut env: WasmEnvMut, sp: u32) {
     let Some(value) = value else {
         fail!("Go indexing out of bounds into {:?} index {index}", source)
     };
-    sp.write_u64(2, value.encode());
+    sp.write_u64(2, value.encode())
 }
 
 pub fn js_value_call(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
@@ -317,21 +370,20 @@ pub fn js_value_call(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
     let pool = &mut data.js_state.pool;
     use JsValue::*;
 
-    let object = JsValue::new(sp.read_u64(0));
-    let method_name_ptr = sp.read_u64(1);
-    let method_name_len = sp.read_u64(2);
-    let method_name = sp.read_slice(method_name_ptr, method_name_len);
-    let args_ptr = sp.read_u64(3);
-    let args_len = sp.read_u64(4);
-    let args = sp.read_value_slice(args_ptr, args_len);
+    let object = JsValue::new(sp.read_u64(0)?);
+    let method_name_ptr = sp.read_u64(1)?;
+    let method_name_len = sp.read_u64(2)?;
+    let method_name = sp.read_slice(method_name_ptr, method_name_len)?;
+    let args_ptr = sp.read_u64(3)?;
+    let args_len = sp.read_u64(4)?;
+    let args = sp.read_value_slice(args_ptr, args_len)?;
     let name = String::from_utf8_lossy(&method_name);
 
     macro_rules! fail {
         ($text:expr $(,$args:expr)*) => {{
             eprintln!($text $(,$args)*);
-            sp.write_u64(6, GoValue::Null.encode());
-            sp.write_u8(7, 1);
-            return Ok(())
+            sp.write_u64(6, GoValue::Null.encode())?;
+            return sp.write_u8(7, 1);
         }};
     }
 
@@ -378,14 +430,9 @@ pub fn js_value_call(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
                         );
                         length = buf.len() - offset;
                     }
-                    if fd == 1. {
-                        let stdout = std::io::stdout();
-                        let mut stdout = stdout.lock();
-                        stdout.write_all(&buf[offset..(offset + length)]).unwrap();
-                    } else if fd == 2. {
-                        let stderr = std::io::stderr();
-                        let mut stderr = stderr.lock();
-                        stderr.write_all(&buf[offset..(offset + length)]).unwrap();
+                    if fd == 1. || fd == 2. {
+                        let text = &buf[offset..(offset + length)];
+                        data.go_state.write_output(fd as u64, text);
                     } else {
                         eprintln!("Go trying to write to unknown FD {}", fd);
                     }
@@ -405,9 +452,19 @@ pub fn js_value_call(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
 
                     // the stack pointer has changed, so we'll need to write our return results elsewhere
                     let pointer = get_stack_pointer.call(&mut store)? as u32;
-                    sp.write_u64_ptr(pointer + sp.relative_offset(6), GoValue::Null.encode());
-                    sp.write_u8_ptr(pointer + sp.relative_offset(7), 1);
-                    return Ok(());
+
+                    // Go clears the event once it's handled, so one left over means Go never ran the callback
+                    if let Some(event) = &env.data().js_state.pending_event {
+                        return Escape::failure(format!(
+                            "Go returned without handling the fs.write callback {:?}",
+                            event.id
+                        ));
+                    }
+                    // a corrupt stack pointer here would otherwise take down the process
+                    let result = pointer.saturating_add(sp.relative_offset(6));
+                    let status = pointer.saturating_add(sp.relative_offset(7));
+                    sp.write_u64_ptr(result, GoValue::Null.encode())?;
+                    return sp.write_u8_ptr(status, 1);
                 }
                 _ => fail!("Go trying to call fs.write with bad args {:?}", args),
             }
@@ -429,6 +486,10 @@ pub fn js_value_call(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
             rng.fill_bytes(buf.as_mut_slice());
             GoValue::Undefined
         }
+        (Ref(PERFORMANCE_ID), b"now") => {
+            // milliseconds, read from the same deterministic clock as nanotime1
+            GoValue::Number(data.go_state.time as f64 / 1_000_000.)
+        }
         (Ref(obj_id), _) => {
             let value = match pool.get(obj_id) {
                 Some(value) => value,
@@ -436,32 +497,34 @@ pub fn js_value_call(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
             };
             match value {
                 DynamicObject::Date => GoValue::Number(0.0),
+                DynamicObject::TextEncoder | DynamicObject::TextDecoder => fail!(
+                    "Go trying to call method {name} for {:?}, which requires unsupported JS strings",
+                    value
+                ),
                 _ => fail!("Go trying to call unknown method {name} for date object"),
             }
         }
         _ => fail!("Go trying to call unknown method {:?} . {name}", object),
     };
 
-    sp.write_u64(6, value.encode());
-    sp.write_u8(7, 1);
-    Ok(())
+    sp.write_u64(6, value.encode())?;
+    sp.write_u8(7, 1)
 }
 
-pub fn js_value_new(mut env: WasmEnvMut, sp: u32) {
+pub fn js_value_new(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
     let (sp, env) = GoStack::new(sp, &mut env);
     let pool = &mut env.js_state.pool;
 
-    let class = sp.read_u32(0);
-    let args_ptr = sp.read_u64(1);
-    let args_len = sp.read_u64(2);
-    let args = sp.read_value_slice(args_ptr, args_len);
+    let class = sp.read_u32(0)?;
+    let args_ptr = sp.read_u64(1)?;
+    let args_len = sp.read_u64(2)?;
+    let args = sp.read_value_slice(args_ptr, args_len)?;
     match class {
         UINT8_ARRAY_ID => match args.first() {
             Some(JsValue::Number(size)) => {
                 let id = pool.insert(DynamicObject::Uint8Array(vec![0; *size as usize]));
-                sp.write_u64(4, GoValue::Object(id).encode());
-                sp.write_u8(5, 1);
-                return;
+                sp.write_u64(4, GoValue::Object(id).encode())?;
+                return sp.write_u8(5, 1);
             }
             _ => eprintln!(
                 "Go attempted to construct Uint8Array with bad args: {:?}",
@@ -470,20 +533,28 @@ pub fn js_value_new(mut env: WasmEnvMut, sp: u32) {
         },
         DATE_ID => {
             let id = pool.insert(DynamicObject::Date);
-            sp.write_u64(4, GoValue::Object(id).encode());
-            sp.write_u8(5, 1);
-            return;
+            sp.write_u64(4, GoValue::Object(id).encode())?;
+            return sp.write_u8(5, 1);
+        }
+        TEXT_ENCODER_ID | TEXT_DECODER_ID => {
+            let codec = match class {
+                TEXT_ENCODER_ID => DynamicObject::TextEncoder,
+                _ => DynamicObject::TextDecoder,
+            };
+            let id = pool.insert(codec);
+            sp.write_u64(4, GoValue::Object(id).encode())?;
+            return sp.write_u8(5, 1);
         }
         _ => eprintln!("Go trying to construct unimplemented JS value {class}"),
     }
-    sp.write_u64(4, GoValue::Null.encode());
-    sp.write_u8(5, 0);
+    sp.write_u64(4, GoValue::Null.encode())?;
+    sp.write_u8(5, 0)
 }
 
-pub fn js_value_length(mut env: WasmEnvMut, sp: u32) {
+pub fn js_value_length(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
     let (sp, env) = GoStack::new(sp, &mut env);
 
-    let source = match JsValue::new(sp.read_u64(0)) {
+    let source = match JsValue::new(sp.read_u64(0)?) {
         JsValue::Ref(x) => env.js_state.pool.get(x),
         _ => None,
     };
@@ -498,14 +569,14 @@ pub fn js_value_length(mut env: WasmEnvMut, sp: u32) {
             0
         }
     };
-    sp.write_u64(1, length as u64);
+    sp.write_u64(1, length as u64)
 }
 
-pub fn js_copy_bytes_to_go(mut env: WasmEnvMut, sp: u32) {
+pub fn js_copy_bytes_to_go(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
     let (sp, env) = GoStack::new(sp, &mut env);
-    let dest_ptr = sp.read_u64(0);
-    let dest_len = sp.read_u64(1);
-    let src_val = JsValue::new(sp.read_u64(3));
+    let dest_ptr = sp.read_u64(0)?;
+    let dest_len = sp.read_u64(1)?;
+    let src_val = JsValue::new(sp.read_u64(3)?);
 
     match src_val {
         JsValue::Ref(src_id) => match env.js_state.pool.get_mut(src_id) {
@@ -517,10 +588,9 @@ pub fn js_copy_bytes_to_go(mut env: WasmEnvMut, sp: u32) {
                     );
                 }
                 let len = std::cmp::min(src_len, dest_len) as usize;
-                sp.write_slice(dest_ptr, &buf[..len]);
-                sp.write_u64(4, GoValue::Number(len as f64).encode());
-                sp.write_u8(5, 1);
-                return;
+                sp.write_slice(dest_ptr, &buf[..len])?;
+                sp.write_u64(4, GoValue::Number(len as f64).encode())?;
+                return sp.write_u8(5, 1);
             }
             source => {
                 eprintln!(
@@ -532,16 +602,16 @@ pub fn js_copy_bytes_to_go(mut env: WasmEnvMut, sp: u32) {
         _ => eprintln!("Go trying to copy bytes from {:?}", src_val),
     }
 
-    sp.write_u8(5, 0);
+    sp.write_u8(5, 0)
 }
 
-pub fn js_copy_bytes_to_js(mut env: WasmEnvMut, sp: u32) {
+pub fn js_copy_bytes_to_js(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
     let (sp, env) = GoStack::new(sp, &mut env);
 
-    match JsValue::new(sp.read_u64(0)) {
+    match JsValue::new(sp.read_u64(0)?) {
         JsValue::Ref(dest_id) => {
-            let src_ptr = sp.read_u64(1);
-            let src_len = sp.read_u64(2);
+            let src_ptr = sp.read_u64(1)?;
+            let src_len = sp.read_u64(2)?;
 
             match env.js_state.pool.get_mut(dest_id) {
                 Some(DynamicObject::Uint8Array(buf)) => {
@@ -554,11 +624,10 @@ pub fn js_copy_bytes_to_js(mut env: WasmEnvMut, sp: u32) {
                     let len = std::cmp::min(src_len, dest_len) as usize;
 
                     // Slightly inefficient as this allocates a new temporary buffer
-                    let data = sp.read_slice(src_ptr, len as u64);
+                    let data = sp.read_slice(src_ptr, len as u64)?;
                     buf[..len].copy_from_slice(&data);
-                    sp.write_u64(4, GoValue::Number(len as f64).encode());
-                    sp.write_u8(5, 1);
-                    return;
+                    sp.write_u64(4, GoValue::Number(len as f64).encode())?;
+                    return sp.write_u8(5, 1);
                 }
                 dest => eprintln!("Go trying to copy bytes into unsupported target {:?}", dest),
             }
@@ -566,8 +635,8 @@ pub fn js_copy_bytes_to_js(mut env: WasmEnvMut, sp: u32) {
         value => eprintln!("Go trying to copy bytes into {:?}", value),
     }
 
-    sp.write_u64(4, GoValue::Null.encode());
-    sp.write_u8(5, 0);
+    sp.write_u64(4, GoValue::Null.encode())?;
+    sp.write_u8(5, 0)
 }
 
 macro_rules! unimpl_js {
@@ -3,7 +3,17 @@
 
 #![cfg(test)]
 
-use wasmer::{imports, Instance, Module, Store, Value};
+use crate::{
+    arbcompress,
+    gostack::{GoRuntimeState, GoStack},
+    machine::{self, Escape, WasmEnv},
+    socket,
+    syscall::{self, GoValue, JsValue, FS_ID, GLOBAL_ID, GO_ID, UINT8_ARRAY_ID},
+    Opts,
+};
+
+use structopt::StructOpt;
+use wasmer::{imports, FunctionEnv, Instance, Memory, MemoryType, Module, Store, Value};
 
 #[test]
 fn test_crate() -> eyre::Result<()> {
@@ -21,3 +31,430 @@ fn test_crate() -> eyre::Result<()> {
     assert_eq!(result[0], Value::I32(43));
     Ok(())
 }
+
+#[test]
+fn test_truncated_memory() -> eyre::Result<()> {
+    let mut store = Store::default();
+    let memory = Memory::new(&mut store, MemoryType::new(1, None, false))?;
+    let env = WasmEnv {
+        memory: Some(memory),
+        ..WasmEnv::default()
+    };
+    let env = FunctionEnv::new(&mut store, env).into_mut(&mut store);
+
+    let size: u32 = 65536;
+    let sp = GoStack::simple(size - 16, &env);
+    assert_eq!(sp.memory_size(), size as u64);
+
+    // every helper must succeed right up to the end of memory and fail one byte past it
+    for end in [size - 64, size - 1, size, size + 1, u32::MAX - 8, u32::MAX] {
+        let fits = |len: u32| end.checked_add(len).map_or(false, |x| x <= size);
+        assert_eq!(sp.read_u8_ptr(end).is_ok(), fits(1));
+        assert_eq!(sp.read_u32_ptr(end).is_ok(), fits(4));
+        assert_eq!(sp.read_u64_ptr(end).is_ok(), fits(8));
+        assert_eq!(sp.write_u8_ptr(end, 1).is_ok(), fits(1));
+        assert_eq!(sp.write_u32_ptr(end, 1).is_ok(), fits(4));
+        assert_eq!(sp.write_u64_ptr(end, 1).is_ok(), fits(8));
+        assert_eq!(sp.read_slice(end.into(), 32).is_ok(), fits(32));
+        assert_eq!(sp.write_slice(end.into(), &[0; 32]).is_ok(), fits(32));
+        assert_eq!(sp.read_value_slice(end.into(), 2).is_ok(), fits(16));
+    }
+
+    // args are read relative to the stack pointer
+    assert!(sp.read_u64(0).is_ok());
+    assert!(sp.write_u64(0, 0).is_ok());
+    assert!(sp.read_u64(1).is_err());
+    assert!(sp.write_u64(1, 0).is_err());
+
+    // pointers and lengths from a corrupt stack mustn't overflow or allocate
+    assert!(sp.read_slice(u64::MAX, 2).is_err());
+    assert!(sp.read_slice(1 << 32, 0).is_err());
+    assert!(sp.read_slice(0, u64::MAX).is_err());
+    assert!(GoStack::simple(u32::MAX, &env).read_u8(0).is_err());
+
+    match sp.read_slice(size.into(), 1) {
+        Err(Escape::Memory(text)) => {
+            assert!(text.contains("0x10000"), "missing pointer: {text}");
+            assert!(text.contains("65536"), "missing memory size: {text}");
+        }
+        _ => panic!("expected a memory escape"),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_js_bootstrap_fields() -> eyre::Result<()> {
+    // a hand-written list of the fields Go 1.20 reads through syscall/js, shared with go-stub.
+    // It isn't a recorded trace; regenerate it with `--record-js-fields` from a replay binary.
+    let trace = include_str!("../../wasm-libraries/go-stub/js-fields.txt");
+    let accesses: Vec<(u32, &str)> = trace
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .map(|line| {
+            let (source, field) = line.split_once(' ').expect("malformed trace");
+            (source.parse().expect("malformed source"), field)
+        })
+        .collect();
+
+    let mut env = WasmEnv::default();
+    env.js_state.accessed_fields = Some(vec![]);
+    for (source, field) in &accesses {
+        syscall::get_field(&mut env, *source, field.as_bytes());
+    }
+    assert!(env.js_state.unknown_fields.is_empty());
+
+    // replaying the list records the same fields back, in the same order
+    let path = std::env::temp_dir().join(format!("js-fields-{}.txt", std::process::id()));
+    syscall::write_js_fields(&path, env.js_state.accessed_fields.as_ref().unwrap())?;
+    let recorded = std::fs::read_to_string(&path)?;
+    std::fs::remove_file(&path)?;
+    let fields = |text: &str| -> Vec<String> {
+        let lines = text.lines().filter(|line| !line.starts_with('#'));
+        lines.map(str::to_owned).collect()
+    };
+    assert_eq!(fields(&recorded), fields(trace));
+
+    // unknown fields are recorded once each, in order
+    syscall::get_field(&mut env, GLOBAL_ID, b"WebSocket");
+    syscall::get_field(&mut env, FS_ID, b"promises");
+    syscall::get_field(&mut env, GLOBAL_ID, b"WebSocket");
+    let unknown: Vec<(u32, String)> =
+        vec![(GLOBAL_ID, "WebSocket".into()), (FS_ID, "promises".into())];
+    assert_eq!(env.js_state.unknown_fields, unknown);
+    Ok(())
+}
+
+#[test]
+fn test_unhandled_callback() -> eyre::Result<()> {
+    // a Go runtime whose resume returns without consuming the pending event
+    let wat = r#"
+        (module
+            (memory (export "mem") 1)
+            (func (export "resume"))
+            (func (export "getsp") (result i32) i32.const 0x800))
+    "#;
+    let mut store = Store::default();
+    let module = Module::new(&store, wat)?;
+    let instance = Instance::new(&mut store, &module, &imports! {})?;
+
+    let mut env = WasmEnv {
+        memory: Some(instance.exports.get_memory("mem")?.clone()),
+        go_state: GoRuntimeState::new(true),
+        ..WasmEnv::default()
+    };
+    env.exports.resume = Some(instance.exports.get_typed_function(&store, "resume")?);
+    env.exports.get_stack_pointer = Some(instance.exports.get_typed_function(&store, "getsp")?);
+    let env = FunctionEnv::new(&mut store, env);
+
+    let (sp, name, args) = (0x100, 0x1000, 0x1100);
+    let number = |x: f64| x.to_bits();
+    let write = |store: &mut Store, ptr: u64, data: &[u8]| {
+        let memory = env.as_ref(&*store).memory.clone().unwrap();
+        memory.view(&*store).write(ptr, data).unwrap();
+    };
+    let write_values = |store: &mut Store, ptr: u64, values: &[u64]| {
+        let data: Vec<u8> = values.iter().flat_map(|x| x.to_le_bytes()).collect();
+        write(store, ptr, &data);
+    };
+    let read_value = |store: &mut Store, arg: u64| {
+        let memory = env.as_ref(&*store).memory.clone().unwrap();
+        let mut data = [0; 8];
+        memory
+            .view(&*store)
+            .read(sp + 8 * (arg + 1), &mut data)
+            .unwrap();
+        JsValue::new(u64::from_le_bytes(data))
+    };
+    let call = |store: &mut Store, object: GoValue, method: &[u8], values: &[u64]| {
+        write(store, name, method);
+        write_values(store, args, values);
+        let stack = [
+            object.encode(),
+            name,
+            method.len() as u64,
+            args,
+            values.len() as u64,
+        ];
+        write_values(store, sp + 8, &stack);
+        syscall::js_value_call(env.clone().into_mut(store), sp as u32)
+    };
+
+    // wrap callback 7 and allocate a buffer to write
+    call(
+        &mut store,
+        GoValue::Object(GO_ID),
+        b"_makeFuncWrapper",
+        &[number(7.)],
+    )?;
+    let JsValue::Ref(callback) = read_value(&mut store, 6) else {
+        panic!("failed to wrap the callback");
+    };
+    write_values(&mut store, sp + 8, &[u64::from(UINT8_ARRAY_ID), args, 1]);
+    write_values(&mut store, args, &[number(4.)]);
+    syscall::js_value_new(env.clone().into_mut(&mut store), sp as u32)?;
+    let JsValue::Ref(buf) = read_value(&mut store, 4) else {
+        panic!("failed to allocate the buffer");
+    };
+
+    let write_args = [
+        number(1.),
+        GoValue::Object(buf).encode(),
+        number(0.),
+        number(4.),
+        GoValue::Null.encode(),
+        GoValue::Function(callback).encode(),
+    ];
+    match call(&mut store, GoValue::Object(FS_ID), b"write", &write_args) {
+        Err(Escape::Failure(text)) => assert!(text.contains("fs.write"), "bad message: {text}"),
+        _ => panic!("expected an unhandled callback to fail"),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_capture_output() -> eyre::Result<()> {
+    let args = [
+        "jit",
+        "--binary",
+        "programs/streams/main.wat",
+        "--cranelift",
+    ];
+    let opts = Opts::from_iter(args);
+    let env = WasmEnv {
+        go_state: GoRuntimeState::new(true),
+        ..WasmEnv::default()
+    };
+    let (instance, env, mut store) = machine::create(&opts, env);
+
+    let run = instance.exports.get_function("run")?;
+    run.call(&mut store, &[Value::I32(0), Value::I32(0)])?;
+
+    let go_state = &mut env.as_mut(&mut store).go_state;
+    let expected = vec![
+        (1, b"hello".to_vec()),
+        (2, b"oops".to_vec()),
+        (1, b"world!".to_vec()),
+    ];
+    assert_eq!(go_state.drain_output(), expected);
+    assert!(go_state.drain_output().is_empty());
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "brotli-dictionary"))]
+fn test_brotli_dictionary_unsupported() {
+    use arbutil::brotli::{self, BrotliError, Dictionaries};
+
+    let input = b"the quick brown fox jumps over the lazy dog, again and again and again";
+    let compressed = brotli::compress(input, 11, 22, None, 1024).unwrap();
+    let output = brotli::decompress(&compressed, None, input.len()).unwrap();
+    assert_eq!(output, input);
+
+    let dictionary = b"the quick brown fox jumps over the lazy dog";
+    let mut dicts = Dictionaries::default();
+    assert_eq!(
+        dicts.set_chain(dictionary.to_vec()),
+        Err(BrotliError::Unsupported)
+    );
+    assert_eq!(
+        brotli::compress(input, 11, 22, Some(dictionary), 1024),
+        Err(BrotliError::Unsupported)
+    );
+}
+
+#[test]
+#[cfg(feature = "brotli-dictionary")]
+fn test_brotli_dictionary() {
+    use arbutil::brotli::{self, BrotliError, Dictionaries, Dictionary};
+
+    let input = b"the quick brown fox jumps over the lazy dog, again and again and again";
+    let mut dicts = Dictionaries::default();
+
+    let compressed = brotli::compress(input, 11, 22, None, 1024).unwrap();
+    let output = brotli::decompress(&compressed, None, input.len()).unwrap();
+    assert_eq!(output, input);
+
+    let chain = Dictionary::Chain.into();
+    assert_eq!(
+        dicts.get(chain),
+        Err(BrotliError::UnsetDictionary(Dictionary::Chain))
+    );
+    let dictionary = b"the quick brown fox jumps over the lazy dog".to_vec();
+    assert_eq!(dicts.set_chain(vec![]), Err(BrotliError::EmptyDictionary));
+    dicts.set_chain(dictionary.clone()).unwrap();
+    assert_eq!(
+        dicts.set_chain(dictionary),
+        Err(BrotliError::AlreadySetDictionary(Dictionary::Chain))
+    );
+
+    let dict = dicts.get(chain).unwrap();
+    let compressed = brotli::compress(input, 11, 22, dict, 1024).unwrap();
+    let output = brotli::decompress(&compressed, dict, input.len()).unwrap();
+    assert_eq!(output, input);
+    let output = brotli::decompress(&compressed, None, input.len()).ok();
+    assert_ne!(output.as_deref(), Some(&input[..]));
+
+    assert_eq!(dicts.get(Dictionary::Empty.into()), Ok(None));
+    assert_eq!(dicts.get(2), Err(BrotliError::UnknownDictionary(2)));
+}
+
+#[test]
+fn test_brotli_hostios() -> eyre::Result<()> {
+    use arbutil::brotli::Dictionary;
+
+    let mut store = Store::default();
+    let memory = Memory::new(&mut store, MemoryType::new(1, None, false))?;
+    let env = WasmEnv {
+        memory: Some(memory),
+        ..WasmEnv::default()
+    };
+    let mut env = FunctionEnv::new(&mut store, env).into_mut(&mut store);
+
+    let input = b"the quick brown fox jumps over the lazy dog, again and again and again";
+    let dictionary = b"the quick brown fox jumps over the lazy dog";
+    let (sp, in_ptr, mid_ptr, out_ptr, dict_ptr) = (0x100, 0x1000, 0x2000, 0x3000, 0x4000);
+    let buf_len = 0x800;
+
+    let stack = GoStack::simple(sp, &env);
+    stack.write_slice(in_ptr, input)?;
+    stack.write_slice(dict_ptr, dictionary)?;
+
+    let write_args = |args: &[u64]| -> eyre::Result<()> {
+        for (arg, value) in args.iter().enumerate() {
+            stack.write_u64(arg as u32, *value)?;
+        }
+        Ok(())
+    };
+
+    // (dictionary []byte)
+    let dict_len = dictionary.len() as u64;
+    write_args(&[dict_ptr, dict_len, dict_len])?;
+    let set = arbcompress::brotli_set_dictionary(env.as_mut(), sp);
+    let dictionaries = if cfg!(feature = "brotli-dictionary") {
+        set?;
+        let again = arbcompress::brotli_set_dictionary(env.as_mut(), sp);
+        assert!(matches!(again, Err(Escape::HostIO(_))));
+        vec![Dictionary::Empty, Dictionary::Chain]
+    } else {
+        assert!(matches!(set, Err(Escape::HostIO(_))));
+        vec![Dictionary::Empty]
+    };
+
+    let in_len = input.len() as u64;
+    for dictionary in dictionaries {
+        let id = u32::from(dictionary);
+
+        // (inBuf []byte, outBuf []byte, level int, windowSize int, dictionary uint32) int
+        write_args(&[
+            in_ptr, in_len, in_len, mid_ptr, buf_len, buf_len, 11, 22, 0, 0,
+        ])?;
+        stack.write_u32(8, id)?;
+        arbcompress::brotli_compress_with_dictionary(env.as_mut(), sp)?;
+        let compressed_len = stack.read_u64(9)?;
+        assert!(compressed_len < buf_len);
+        assert_eq!(stack.read_u32(8)?, id);
+
+        // (inBuf []byte, outBuf []byte, dictionary uint32) int
+        let len = compressed_len;
+        write_args(&[mid_ptr, len, len, out_ptr, buf_len, buf_len, 0, 0])?;
+        stack.write_u32(6, id)?;
+        arbcompress::brotli_decompress_with_dictionary(env.as_mut(), sp)?;
+        assert_eq!(stack.read_u64(7)?, in_len);
+        assert_eq!(stack.read_u32(6)?, id);
+        assert_eq!(stack.read_slice(out_ptr, in_len)?, input);
+    }
+
+    // unknown dictionaries fail without touching the output buffer
+    let unknown = 2;
+    stack.write_slice(out_ptr, &[0; 8])?;
+    write_args(&[
+        in_ptr, in_len, in_len, out_ptr, buf_len, buf_len, 11, 22, 0, 0,
+    ])?;
+    stack.write_u32(8, unknown)?;
+    arbcompress::brotli_compress_with_dictionary(env.as_mut(), sp)?;
+    assert_eq!(stack.read_u64(9)?, u64::MAX);
+    assert_eq!(stack.read_slice(out_ptr, 8)?, [0; 8]);
+
+    write_args(&[mid_ptr, 16, 16, out_ptr, buf_len, buf_len, 0, 0])?;
+    stack.write_u32(6, unknown)?;
+    arbcompress::brotli_decompress_with_dictionary(env.as_mut(), sp)?;
+    assert_eq!(stack.read_u64(7)?, u64::MAX);
+    assert_eq!(stack.read_slice(out_ptr, 8)?, [0; 8]);
+    Ok(())
+}
+
+#[test]
+fn test_error_codes() {
+    use arbutil::ErrorCode;
+    use std::io;
+
+    // these values cross the socket to Go, so they must never change
+    let stable = [
+        (ErrorCode::Memory, 0),
+        (ErrorCode::OutOfInk, 1),
+        (ErrorCode::OutOfStack, 2),
+        (ErrorCode::Logical, 3),
+        (ErrorCode::Internal, 4),
+        (ErrorCode::Timeout, 5),
+        (ErrorCode::Cancelled, 6),
+        (ErrorCode::ProtocolViolation, 7),
+    ];
+    for (code, value) in stable {
+        assert_eq!(u16::from(code), value);
+        assert_eq!(ErrorCode::try_from(value).unwrap(), code);
+    }
+
+    let escapes = [
+        (Escape::Exit(1), ErrorCode::Logical),
+        (Escape::HostIO("bad args".into()), ErrorCode::Logical),
+        (Escape::Failure("trap".into()), ErrorCode::Internal),
+        (Escape::Memory("out of bounds".into()), ErrorCode::Memory),
+        (
+            Escape::SocketError(io::Error::from(io::ErrorKind::BrokenPipe)),
+            ErrorCode::ProtocolViolation,
+        ),
+    ];
+    for (escape, code) in escapes {
+        assert_eq!(ErrorCode::from(&escape), code);
+    }
+
+    assert_eq!(ErrorCode::Memory.prefix("oops"), b"\0\0oops");
+    assert_eq!(ErrorCode::ProtocolViolation.prefix(""), [0, 7]);
+}
+
+#[test]
+fn test_send_results() -> eyre::Result<()> {
+    use arbutil::{Bytes32, ErrorCode};
+    use std::io::{BufRead, BufReader, BufWriter};
+
+    let mut env = WasmEnv::default();
+    env.small_globals = [3, 7];
+    env.large_globals = [Bytes32::from(11u64), Bytes32([0xab; 32])];
+
+    // failures are the FAILURE byte, then a length-prefixed buffer of the code and message
+    let mut writer = BufWriter::new(vec![]);
+    let error = (ErrorCode::Timeout, "took too long".to_owned());
+    env.write_results(&mut writer, Some(error), 0)?;
+    let data = writer.into_inner()?;
+    let reader = &mut BufReader::new(data.as_slice());
+    assert_eq!(socket::read_u8(reader)?, socket::FAILURE);
+    let message = socket::read_bytes(reader)?;
+    assert_eq!(message.len(), 2 + "took too long".len());
+    assert_eq!(message[..2], u16::from(ErrorCode::Timeout).to_be_bytes());
+    assert_eq!(&message[2..], b"took too long");
+    assert!(reader.fill_buf()?.is_empty());
+
+    // successes are the SUCCESS byte, then the globals and memory used
+    let mut writer = BufWriter::new(vec![]);
+    env.write_results(&mut writer, None, 1 << 20)?;
+    let data = writer.into_inner()?;
+    let reader = &mut BufReader::new(data.as_slice());
+    assert_eq!(socket::read_u8(reader)?, socket::SUCCESS);
+    assert_eq!(socket::read_u64(reader)?, 3);
+    assert_eq!(socket::read_u64(reader)?, 7);
+    assert_eq!(socket::read_bytes32(reader)?, env.large_globals[0]);
+    assert_eq!(socket::read_bytes32(reader)?, env.large_globals[1]);
+    assert_eq!(socket::read_u64(reader)?, 1 << 20);
+    assert!(reader.fill_buf()?.is_empty());
+    Ok(())
+}
@@ -7,7 +7,7 @@ use crate::{
     socket,
 };
 
-use arbutil::{Color, PreimageType};
+use arbutil::{Bytes32, Color, PreimageType};
 use std::{
     io,
     io::{BufReader, BufWriter, ErrorKind},
@@ -15,15 +15,13 @@ use std::{
     time::Instant,
 };
 
-pub type Bytes32 = [u8; 32];
-
 pub fn get_global_state_bytes32(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
     let (sp, env) = GoStack::new(sp, &mut env);
     ready_hostio(env)?;
 
-    let global = sp.read_u64(0) as u32 as usize;
-    let out_ptr = sp.read_u64(1);
-    let mut out_len = sp.read_u64(2) as usize;
+    let global = sp.read_u64(0)? as u32 as usize;
+    let out_ptr = sp.read_u64(1)?;
+    let mut out_len = sp.read_u64(2)? as usize;
     if out_len < 32 {
         eprintln!("Go trying to read block hash into {out_len} bytes long buffer");
     } else {
@@ -34,26 +32,25 @@ pub fn get_global_state_bytes32(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
         Some(global) => global,
         None => return Escape::hostio("global read out of bounds in wavmio.getGlobalStateBytes32"),
     };
-    sp.write_slice(out_ptr, &global[..out_len]);
-    Ok(())
+    sp.write_slice(out_ptr, &global[..out_len])
 }
 
 pub fn set_global_state_bytes32(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
     let (sp, env) = GoStack::new(sp, &mut env);
     ready_hostio(env)?;
 
-    let global = sp.read_u64(0) as u32 as usize;
-    let src_ptr = sp.read_u64(1);
-    let src_len = sp.read_u64(2);
+    let global = sp.read_u64(0)? as u32 as usize;
+    let src_ptr = sp.read_u64(1)?;
+    let src_len = sp.read_u64(2)?;
     if src_len != 32 {
         eprintln!("Go trying to set 32-byte global with a {src_len} bytes long buffer");
         return Ok(());
     }
 
-    let slice = sp.read_slice(src_ptr, src_len);
-    let slice = &slice.try_into().unwrap();
+    let slice = sp.read_slice(src_ptr, src_len)?;
+    let slice: [u8; 32] = slice.try_into().unwrap();
     match env.large_globals.get_mut(global) {
-        Some(global) => *global = *slice,
+        Some(global) => *global = slice.into(),
         None => {
             return Escape::hostio("global write out of bounds in wavmio.setGlobalStateBytes32")
         }
@@ -65,21 +62,20 @@ pub fn get_global_state_u64(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
     let (sp, env) = GoStack::new(sp, &mut env);
     ready_hostio(env)?;
 
-    let global = sp.read_u64(0) as u32 as usize;
+    let global = sp.read_u64(0)? as u32 as usize;
     match env.small_globals.get(global) {
         Some(global) => sp.write_u64(1, *global),
-        None => return Escape::hostio("global read out of bounds in wavmio.getGlobalStateU64"),
+        None => Escape::hostio("global read out of bounds in wavmio.getGlobalStateU64"),
     }
-    Ok(())
 }
 
 pub fn set_global_state_u64(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
     let (sp, env) = GoStack::new(sp, &mut env);
     ready_hostio(env)?;
 
-    let global = sp.read_u64(0) as u32 as usize;
+    let global = sp.read_u64(0)? as u32 as usize;
     match env.small_globals.get_mut(global) {
-        Some(global) => *global = sp.read_u64(1),
+        Some(global) => *global = sp.read_u64(1)?,
         None => return Escape::hostio("global write out of bounds in wavmio.setGlobalStateU64"),
     }
     Ok(())
@@ -104,14 +100,13 @@ pub fn read_delayed_inbox_message(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
 /// Reads an inbox message
 /// note: the order of the checks is very important.
 fn inbox_message_impl(sp: &GoStack, inbox: &Inbox, name: &str) -> MaybeEscape {
-    let msg_num = sp.read_u64(0);
-    let offset = sp.read_u64(1);
-    let out_ptr = sp.read_u64(2);
-    let out_len = sp.read_u64(3);
+    let msg_num = sp.read_u64(0)?;
+    let offset = sp.read_u64(1)?;
+    let out_ptr = sp.read_u64(2)?;
+    let out_len = sp.read_u64(3)?;
     if out_len != 32 {
         eprintln!("Go trying to read inbox message with out len {out_len} in {name}");
-        sp.write_u64(5, 0);
-        return Ok(());
+        return sp.write_u64(5, 0);
     }
 
     macro_rules! error {
@@ -136,9 +131,8 @@ fn inbox_message_impl(sp: &GoStack, inbox: &Inbox, name: &str) -> MaybeEscape {
 
     let len = std::cmp::min(32, message.len().saturating_sub(offset));
     let read = message.get(offset..(offset + len)).unwrap_or_default();
-    sp.write_slice(out_ptr, read);
-    sp.write_u64(5, read.len() as u64);
-    Ok(())
+    sp.write_slice(out_ptr, read)?;
+    sp.write_u64(5, read.len() as u64)
 }
 
 #[deprecated] // we're just keeping this around until we no longer need to validate old replay binaries
@@ -149,7 +143,7 @@ pub fn resolve_keccak_preimage(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
 
 pub fn resolve_typed_preimage(mut env: WasmEnvMut, sp: u32) -> MaybeEscape {
     let (mut sp, env) = GoStack::new(sp, &mut env);
-    let preimage_type = sp.read_u8(0);
+    let preimage_type = sp.read_u8(0)?;
     sp.shift_start(8); // to account for the preimage type being the first slot
     resolve_preimage_impl(env, sp, preimage_type, "wavmio.ResolveTypedPreimage")
 }
@@ -160,21 +154,19 @@ pub fn resolve_preimage_impl(
     preimage_type: u8,
     name: &str,
 ) -> MaybeEscape {
-    let hash_ptr = sp.read_u64(0);
-    let hash_len = sp.read_u64(1);
-    let offset = sp.read_u64(3);
-    let out_ptr = sp.read_u64(4);
-    let out_len = sp.read_u64(5);
+    let hash_ptr = sp.read_u64(0)?;
+    let hash_len = sp.read_u64(1)?;
+    let offset = sp.read_u64(3)?;
+    let out_ptr = sp.read_u64(4)?;
+    let out_len = sp.read_u64(5)?;
     if hash_len != 32 || out_len != 32 {
         eprintln!("Go trying to resolve pre image with hash len {hash_len} and out len {out_len}");
-        sp.write_u64(7, 0);
-        return Ok(());
+        return sp.write_u64(7, 0);
     }
 
     let Ok(preimage_type) = preimage_type.try_into() else {
         eprintln!("Go trying to resolve pre image with unknown type {preimage_type}");
-        sp.write_u64(7, 0);
-        return Ok(());
+        return sp.write_u64(7, 0);
     };
 
     macro_rules! error {
@@ -184,11 +176,12 @@ pub fn resolve_preimage_impl(
         }};
     }
 
-    let hash = sp.read_slice(hash_ptr, hash_len);
-    let hash: &[u8; 32] = &hash.try_into().unwrap();
+    let hash = sp.read_slice(hash_ptr, hash_len)?;
+    let hash: [u8; 32] = hash.try_into().unwrap();
+    let hash = Bytes32::from(hash);
     let hash_hex = hex::encode(hash);
 
-    let Some(preimage) = env.preimages.get(&preimage_type).and_then(|m| m.get(hash)) else {
+    let Some(preimage) = env.preimages.get(&preimage_type).and_then(|m| m.get(&hash)) else {
         error!("Missing requested preimage for preimage type {preimage_type:?} hash {hash_hex} in {name}");
     };
 
@@ -199,9 +192,8 @@ pub fn resolve_preimage_impl(
 
     let len = std::cmp::min(32, preimage.len().saturating_sub(offset));
     let read = preimage.get(offset..(offset + len)).unwrap_or_default();
-    sp.write_slice(out_ptr, read);
-    sp.write_u64(7, read.len() as u64);
-    Ok(())
+    sp.write_slice(out_ptr, read)?;
+    sp.write_u64(7, read.len() as u64)
 }
 
 fn ready_hostio(env: &mut WasmEnv) -> MaybeEscape {
@@ -122,8 +122,27 @@ pub unsafe extern "C" fn atomic_u8_store(ptr: *mut u8, contents: u8) {
     (*(ptr as *mut AtomicU8)).store(contents, atomic::Ordering::Relaxed);
 }
 
+/// The longest error string passed to Go, beyond which the message is cut short.
+const MAX_ERROR_LEN: usize = 4096;
+
+/// Caps an error's length, noting how much was removed.
+fn truncate_error(mut err: String) -> String {
+    if err.len() <= MAX_ERROR_LEN {
+        return err;
+    }
+    let mut end = MAX_ERROR_LEN;
+    while !err.is_char_boundary(end) {
+        end -= 1;
+    }
+    let removed = err.len() - end;
+    err.truncate(end);
+    err.push_str(&format!(" [truncated {removed} bytes]"));
+    err
+}
+
 fn err_to_c_string(err: eyre::Report) -> *mut libc::c_char {
-    let err = format!("{:#}", err);
+    // the alternate format prints the error chain on one line without a backtrace
+    let err = truncate_error(format!("{:#}", err));
     unsafe {
         let buf = libc::malloc(err.len() + 1);
         if buf.is_null() {
@@ -351,3 +370,36 @@ pub unsafe extern "C" fn arbitrator_gen_proof(mach: *mut Machine) -> RustByteArr
 pub unsafe extern "C" fn arbitrator_free_proof(proof: RustByteArray) {
     drop(Vec::from_raw_parts(proof.ptr, proof.len, proof.capacity))
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{truncate_error, MAX_ERROR_LEN};
+    use eyre::WrapErr;
+
+    #[test]
+    pub fn test_truncate_error() {
+        let mut err: eyre::Result<()> = Err(eyre::eyre!("root cause"));
+        for i in 0..1000 {
+            err = err.wrap_err(format!("context layer {i}"));
+        }
+        let err = format!("{:#}", err.unwrap_err());
+        assert!(err.len() > MAX_ERROR_LEN);
+
+        let removed = err.len() - MAX_ERROR_LEN;
+        let truncated = truncate_error(err.clone());
+        assert_eq!(
+            truncated.len(),
+            MAX_ERROR_LEN + format!(" [truncated {removed} bytes]").len()
+        );
+        assert!(truncated.starts_with(&err[..MAX_ERROR_LEN]));
+        assert!(truncated.ends_with(&format!("[truncated {removed} bytes]")));
+
+        let short = "short error".to_string();
+        assert_eq!(truncate_error(short.clone()), short);
+
+        // never split a multi-byte character
+        let wide = "é".repeat(MAX_ERROR_LEN);
+        let truncated = truncate_error(wide);
+        assert!(truncated.starts_with(&"é".repeat(MAX_ERROR_LEN / 2)));
+    }
+}
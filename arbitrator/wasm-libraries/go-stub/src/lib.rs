@@ -235,6 +235,16 @@ pub unsafe extern "C" fn go__syscall_js_valueNew(sp: GoStack) {
         sp.write_u64(4, GoValue::Object(id).encode());
         sp.write_u8(5, 1);
         return;
+    } else if class == TEXT_ENCODER_ID {
+        let id = DynamicObjectPool::singleton().insert(DynamicObject::TextEncoder);
+        sp.write_u64(4, GoValue::Object(id).encode());
+        sp.write_u8(5, 1);
+        return;
+    } else if class == TEXT_DECODER_ID {
+        let id = DynamicObjectPool::singleton().insert(DynamicObject::TextDecoder);
+        sp.write_u64(4, GoValue::Object(id).encode());
+        sp.write_u8(5, 1);
+        return;
     } else {
         eprintln!(
             "Go attempting to construct unimplemented JS value {}",
@@ -432,6 +442,9 @@ unsafe fn value_call_impl(sp: &mut GoStack) -> Result<GoValue, String> {
             }
         }
         Ok(GoValue::Undefined)
+    } else if object == InterpValue::Ref(PERFORMANCE_ID) && &method_name == b"now" {
+        // milliseconds, read from the same deterministic clock as nanotime1
+        Ok(GoValue::Number(TIME as f64 / 1_000_000.))
     } else if let InterpValue::Ref(obj_id) = object {
         let val = DynamicObjectPool::singleton().get(obj_id);
         if let Some(DynamicObject::Date) = val {
@@ -443,6 +456,12 @@ unsafe fn value_call_impl(sp: &mut GoStack) -> Result<GoValue, String> {
                     String::from_utf8_lossy(&method_name),
                 ));
             }
+        } else if let Some(DynamicObject::TextEncoder | DynamicObject::TextDecoder) = val {
+            return Err(format!(
+                "Go attempting to call method {} for {:?}, which requires unsupported JS strings",
+                String::from_utf8_lossy(&method_name),
+                val,
+            ));
         } else {
             return Err(format!(
                 "Go attempting to call method {} for unknown object - id {}",
@@ -581,6 +600,42 @@ pub unsafe extern "C" fn go__syscall_js_finalizeRef(sp: GoStack) {
     }
 }
 
+/// Returns the number of distinct JS fields Go accessed that have no shim.
+/// Harnesses can check this after running a new Go toolchain, then read each entry with
+/// `go_stub__unknown_field_source` and `go_stub__unknown_field`.
+#[no_mangle]
+pub unsafe extern "C" fn go_stub__unknown_field_count() -> usize {
+    UNKNOWN_FIELDS.len()
+}
+
+/// Returns the id of the JS value the `index`th unknown field was accessed on,
+/// or `u32::MAX` if there's no such entry.
+#[no_mangle]
+pub unsafe extern "C" fn go_stub__unknown_field_source(index: usize) -> u32 {
+    match UNKNOWN_FIELDS.get(index) {
+        Some((source, _)) => *source,
+        None => u32::MAX,
+    }
+}
+
+/// Copies up to `out_len` bytes of the `index`th unknown field's name into the caller's memory.
+/// Returns the name's full length, which may exceed `out_len`, or `usize::MAX` if there's no such entry.
+#[no_mangle]
+pub unsafe extern "C" fn go_stub__unknown_field(
+    index: usize,
+    out_ptr: usize,
+    out_len: usize,
+) -> usize {
+    match UNKNOWN_FIELDS.get(index) {
+        Some((_, field)) => {
+            let len = std::cmp::min(field.len(), out_len);
+            write_slice(&field[..len], out_ptr as u64);
+            field.len()
+        }
+        None => usize::MAX,
+    }
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn wavm__go_after_run() {
     let mut state = TIMEOUT_STATE.get_or_insert_with(Default::default);
@@ -12,6 +12,9 @@ pub const FS_ID: u32 = 103;
 pub const UINT8_ARRAY_ID: u32 = 104;
 pub const CRYPTO_ID: u32 = 105;
 pub const DATE_ID: u32 = 106;
+pub const TEXT_ENCODER_ID: u32 = 107;
+pub const TEXT_DECODER_ID: u32 = 108;
+pub const PERFORMANCE_ID: u32 = 109;
 
 pub const FS_CONSTANTS_ID: u32 = 200;
 
@@ -88,6 +91,8 @@ pub enum DynamicObject {
     PendingEvent(PendingEvent),
     ValueArray(Vec<GoValue>),
     Date,
+    TextEncoder,
+    TextDecoder,
 }
 
 #[derive(Default, Debug)]
@@ -131,6 +136,18 @@ impl DynamicObjectPool {
 
 pub static mut PENDING_EVENT: Option<PendingEvent> = None;
 
+/// The distinct (source, field) pairs Go tried to access without a shim, in order of first access.
+pub static mut UNKNOWN_FIELDS: Vec<(u32, Vec<u8>)> = Vec::new();
+
+unsafe fn record_unknown_field(source: u32, field: &[u8]) {
+    let known = UNKNOWN_FIELDS
+        .iter()
+        .any(|(s, f)| *s == source && f == field);
+    if !known {
+        UNKNOWN_FIELDS.push((source, field.to_vec()));
+    }
+}
+
 pub unsafe fn get_field(source: u32, field: &[u8]) -> GoValue {
     if source == GLOBAL_ID {
         if field == b"Object" {
@@ -147,6 +164,12 @@ pub unsafe fn get_field(source: u32, field: &[u8]) -> GoValue {
             return GoValue::Object(CRYPTO_ID);
         } else if field == b"Date" {
             return GoValue::Object(DATE_ID);
+        } else if field == b"TextEncoder" {
+            return GoValue::Function(TEXT_ENCODER_ID);
+        } else if field == b"TextDecoder" {
+            return GoValue::Function(TEXT_DECODER_ID);
+        } else if field == b"performance" {
+            return GoValue::Object(PERFORMANCE_ID);
         } else if field == b"fetch" {
             // Triggers a code path in Go for a fake network implementation
             return GoValue::Undefined;
@@ -174,8 +197,8 @@ pub unsafe fn get_field(source: u32, field: &[u8]) -> GoValue {
         }
     }
 
-    if let Some(source) = DynamicObjectPool::singleton().get(source).cloned() {
-        if let DynamicObject::PendingEvent(event) = &source {
+    if let Some(object) = DynamicObjectPool::singleton().get(source).cloned() {
+        if let DynamicObject::PendingEvent(event) = &object {
             if field == b"id" {
                 return event.id.assume_num_or_object();
             } else if field == b"this" {
@@ -189,9 +212,10 @@ pub unsafe fn get_field(source: u32, field: &[u8]) -> GoValue {
 
         eprintln!(
             "Go attempting to access unimplemented unknown JS value {:?} field {}",
-            source,
+            object,
             String::from_utf8_lossy(field),
         );
+        record_unknown_field(source, field);
         GoValue::Undefined
     } else {
         eprintln!(
@@ -199,6 +223,37 @@ pub unsafe fn get_field(source: u32, field: &[u8]) -> GoValue {
             source,
             String::from_utf8_lossy(field),
         );
+        record_unknown_field(source, field);
         GoValue::Undefined
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_js_bootstrap_fields() {
+        // a hand-written list of the fields Go 1.20 reads through syscall/js, shared with the jit.
+        // It isn't a recorded trace; regenerate it with the jit's `--record-js-fields`.
+        let trace = include_str!("../js-fields.txt");
+        unsafe {
+            for line in trace.lines().filter(|line| !line.starts_with('#')) {
+                let (source, field) = line.split_once(' ').expect("malformed trace");
+                let source = source.parse().expect("malformed source");
+                get_field(source, field.as_bytes());
+            }
+            assert!(UNKNOWN_FIELDS.is_empty());
+
+            // unknown fields are recorded once each, in order
+            get_field(GLOBAL_ID, b"WebSocket");
+            get_field(FS_ID, b"promises");
+            get_field(GLOBAL_ID, b"WebSocket");
+            let unknown = vec![
+                (GLOBAL_ID, b"WebSocket".to_vec()),
+                (FS_ID, b"promises".to_vec()),
+            ];
+            assert_eq!(UNKNOWN_FIELDS, unknown);
+        }
+    }
+}
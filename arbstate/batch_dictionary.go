@@ -0,0 +1,26 @@
+// Copyright 2024, Offchain Labs, Inc.
+// For license information, see https://github.com/nitro/blob/master/LICENSE
+
+package arbstate
+
+import (
+	_ "embed"
+
+	"github.com/offchainlabs/nitro/arbcompress"
+)
+
+// batchDictionary is the brotli dictionary for batches with the BrotliDictionaryMessageHeaderByte header.
+// The node and the replay binary must agree on it, so it's compiled into both, and the module root commits to it.
+// It's empty unless a chain provides one, in which case dictionary-compressed batches are undecodable.
+//
+//go:embed batch_dictionary.bin
+var batchDictionary []byte
+
+// LoadBatchDictionary registers the chain's batch dictionary with arbcompress, if the chain has one.
+// It must be called once at startup, before any batches are parsed.
+func LoadBatchDictionary() error {
+	if len(batchDictionary) == 0 {
+		return nil
+	}
+	return arbcompress.SetDictionary(batchDictionary)
+}
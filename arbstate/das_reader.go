@@ -55,8 +55,11 @@ const ZgMessageHeaderFlag byte = 0x0c
 // BrotliMessageHeaderByte indicates that the message is brotli-compressed.
 const BrotliMessageHeaderByte byte = 0
 
+// BrotliDictionaryMessageHeaderByte indicates that the message is brotli-compressed with the chain's batch dictionary.
+const BrotliDictionaryMessageHeaderByte byte = 0x01
+
 // KnownHeaderBits is all header bits with known meaning to this nitro version
-const KnownHeaderBits byte = DASMessageHeaderFlag | TreeDASMessageHeaderFlag | L1AuthenticatedMessageHeaderFlag | ZeroheavyMessageHeaderFlag | BlobHashesHeaderFlag | BrotliMessageHeaderByte
+const KnownHeaderBits byte = DASMessageHeaderFlag | TreeDASMessageHeaderFlag | L1AuthenticatedMessageHeaderFlag | ZeroheavyMessageHeaderFlag | BlobHashesHeaderFlag | BrotliMessageHeaderByte | BrotliDictionaryMessageHeaderByte
 
 // hasBits returns true if `checking` has all `bits`
 func hasBits(checking byte, bits byte) bool {
@@ -87,6 +90,10 @@ func IsBrotliMessageHeaderByte(b uint8) bool {
 	return b == BrotliMessageHeaderByte
 }
 
+func IsBrotliDictionaryMessageHeaderByte(b uint8) bool {
+	return b == BrotliDictionaryMessageHeaderByte
+}
+
 func IsZgMessageHeaderByte(header byte) bool {
 	return hasBits(header, ZgMessageHeaderFlag)
 }
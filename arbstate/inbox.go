@@ -131,8 +131,13 @@ func parseSequencerMessage(ctx context.Context, batchNum uint64, batchBlockHash
 	}
 
 	// Stage 3: Decompress the brotli payload and fill the parsedMsg.segments list.
-	if len(payload) > 0 && IsBrotliMessageHeaderByte(payload[0]) {
-		decompressed, err := arbcompress.Decompress(payload[1:], MaxDecompressedLen)
+	// A chain without a batch dictionary treats dictionary-compressed batches as undecodable.
+	if len(payload) > 0 && (IsBrotliMessageHeaderByte(payload[0]) || IsBrotliDictionaryMessageHeaderByte(payload[0])) {
+		dictionary := arbcompress.EmptyDictionary
+		if IsBrotliDictionaryMessageHeaderByte(payload[0]) {
+			dictionary = arbcompress.ChainDictionary
+		}
+		decompressed, err := arbcompress.DecompressWithDictionary(payload[1:], MaxDecompressedLen, dictionary)
 		if err == nil {
 			reader := bytes.NewReader(decompressed)
 			stream := rlp.NewStream(reader, uint64(MaxDecompressedLen))
@@ -0,0 +1,78 @@
+// Copyright 2024, Offchain Labs, Inc.
+// For license information, see https://github.com/nitro/blob/master/LICENSE
+
+//go:build !js && brotli_dictionary
+// +build !js,brotli_dictionary
+
+package arbstate
+
+import (
+	"bytes"
+	"context"
+	"testing"
+
+	"github.com/ethereum/go-ethereum/common"
+	"github.com/ethereum/go-ethereum/rlp"
+	"github.com/offchainlabs/nitro/arbcompress"
+)
+
+func TestDictionaryCompressedBatch(t *testing.T) {
+	ctx := context.Background()
+	dictionary := []byte("This is a long and repetitive string. The quick brown fox jumped over the lazy dog.")
+	l2msg := []byte("The quick brown fox jumped over the lazy dog. This is a long and repetitive string.")
+
+	rawSegment := append([]byte{BatchSegmentKindL2Message}, l2msg...)
+	segment, err := rlp.EncodeToBytes(rawSegment)
+	if err != nil {
+		t.Fatal(err)
+	}
+	batch := func(header byte, payload []byte) []byte {
+		data := make([]byte, 40)
+		data = append(data, header)
+		return append(data, payload...)
+	}
+
+	// without the chain dictionary, dictionary-compressed batches are undecodable
+	compressed, err := arbcompress.CompressWell(segment)
+	if err != nil {
+		t.Fatal(err)
+	}
+	parsed, err := parseSequencerMessage(ctx, 0, common.Hash{}, batch(BrotliDictionaryMessageHeaderByte, compressed), nil, KeysetValidate)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if len(parsed.segments) != 0 {
+		t.Fatal("decoded a dictionary batch without the dictionary")
+	}
+
+	if err := arbcompress.SetDictionary(dictionary); err != nil {
+		t.Fatal(err)
+	}
+	compressed, err = arbcompress.CompressWithDictionary(segment, arbcompress.LEVEL_WELL, arbcompress.ChainDictionary)
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	backend := &multiplexerBackend{
+		batch: batch(BrotliDictionaryMessageHeaderByte, compressed),
+	}
+	multiplexer := NewInboxMultiplexer(backend, 0, nil, KeysetValidate)
+	msg, err := multiplexer.Pop(ctx)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if msg == nil || !bytes.Equal(msg.Message.L2msg, l2msg) {
+		t.Fatal("wrong message replayed", msg)
+	}
+
+	// the plain brotli header doesn't apply the dictionary
+	parsed, err = parseSequencerMessage(ctx, 0, common.Hash{}, batch(BrotliMessageHeaderByte, compressed), nil, KeysetValidate)
+	if err != nil {
+		t.Fatal(err)
+	}
+	for _, decoded := range parsed.segments {
+		if bytes.Equal(decoded, rawSegment) {
+			t.Fatal("decoded a dictionary batch without the dictionary")
+		}
+	}
+}
@@ -215,6 +215,11 @@ func mainImpl() int {
 
 	log.Info("Running Arbitrum nitro node", "revision", vcsRevision, "vcs.time", vcsTime)
 
+	if err := arbstate.LoadBatchDictionary(); err != nil {
+		log.Error("failed to load batch dictionary", "err", err)
+		return 1
+	}
+
 	if nodeConfig.Node.Dangerous.NoL1Listener {
 		nodeConfig.Node.ParentChainReader.Enable = false
 		nodeConfig.Node.BatchPoster.Enable = false
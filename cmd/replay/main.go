@@ -188,6 +188,10 @@ func main() {
 	wavmio.StubInit()
 	gethhook.RequireHookedGeth()
 
+	if err := arbstate.LoadBatchDictionary(); err != nil {
+		panic(fmt.Sprintf("Error loading batch dictionary: %v", err.Error()))
+	}
+
 	glogger := log.NewGlogHandler(log.StreamHandler(os.Stderr, log.TerminalFormat(false)))
 	glogger.Verbosity(log.LvlError)
 	log.Root().SetHandler(glogger)
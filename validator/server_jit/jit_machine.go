@@ -23,6 +23,61 @@ import (
 
 var jitWasmMemoryUsage = metrics.NewRegisteredHistogram("jit/wasm/memoryusage", nil, metrics.NewBoundedHistogramSample())
 
+// JitErrorCode classifies a jit failure.
+// These values must be kept in sync with `arbitrator/arbutil/src/types.rs`.
+type JitErrorCode uint16
+
+const (
+	JitErrorMemory JitErrorCode = iota
+	JitErrorOutOfInk
+	JitErrorOutOfStack
+	JitErrorLogical
+	JitErrorInternal
+	JitErrorTimeout
+	JitErrorCancelled
+	JitErrorProtocolViolation
+)
+
+// JitError is a failure reported by the jit, prefixed on the wire by its two-byte code.
+type JitError struct {
+	Code    JitErrorCode
+	Message string
+}
+
+func (c JitErrorCode) String() string {
+	switch c {
+	case JitErrorMemory:
+		return "Memory"
+	case JitErrorOutOfInk:
+		return "OutOfInk"
+	case JitErrorOutOfStack:
+		return "OutOfStack"
+	case JitErrorLogical:
+		return "Logical"
+	case JitErrorInternal:
+		return "Internal"
+	case JitErrorTimeout:
+		return "Timeout"
+	case JitErrorCancelled:
+		return "Cancelled"
+	case JitErrorProtocolViolation:
+		return "ProtocolViolation"
+	default:
+		return fmt.Sprintf("JitErrorCode(%d)", uint16(c))
+	}
+}
+
+func (e *JitError) Error() string {
+	return e.Message
+}
+
+const (
+	successByte = 0x0
+	failureByte = 0x1
+	anotherByte = 0x3
+	readyByte   = 0x4
+)
+
 type JitMachine struct {
 	binary               string
 	process              *exec.Cmd
@@ -145,11 +200,6 @@ func (machine *JitMachine) prove(
 		return state, err
 	}
 
-	const successByte = 0x0
-	const failureByte = 0x1
-	const anotherByte = 0x3
-	const readyByte = 0x4
-
 	success := []byte{successByte}
 	another := []byte{anotherByte}
 	ready := []byte{readyByte}
@@ -213,6 +263,13 @@ func (machine *JitMachine) prove(
 		return state, err
 	}
 
+	return machine.readResult(conn)
+}
+
+// readResult reads the outcome the jit reports once it finishes proving.
+func (machine *JitMachine) readResult(conn io.Reader) (validator.GoGlobalState, error) {
+	state := validator.GoGlobalState{}
+
 	read := func(count uint64) ([]byte, error) {
 		slice := make([]byte, count)
 		_, err := io.ReadFull(conn, slice)
@@ -251,8 +308,12 @@ func (machine *JitMachine) prove(
 			if err != nil {
 				return state, err
 			}
-			log.Error("Jit Machine Failure", "message", string(message))
-			return state, errors.New(string(message))
+			if len(message) < 2 {
+				return state, errors.New("inter-process communication failure")
+			}
+			code := JitErrorCode(binary.BigEndian.Uint16(message[:2]))
+			log.Error("Jit Machine Failure", "code", code, "message", string(message[2:]))
+			return state, &JitError{Code: code, Message: string(message[2:])}
 		case successByte:
 			if state.Batch, err = readUint64(); err != nil {
 				return state, err
@@ -0,0 +1,79 @@
+// Copyright 2024, Offchain Labs, Inc.
+// For license information, see https://github.com/nitro/blob/master/LICENSE
+
+package server_jit
+
+import (
+	"bytes"
+	"encoding/binary"
+	"errors"
+	"testing"
+
+	"github.com/ethereum/go-ethereum/common"
+)
+
+func frameFailure(code JitErrorCode, message string) []byte {
+	payload := binary.BigEndian.AppendUint16(nil, uint16(code))
+	payload = append(payload, message...)
+	frame := []byte{failureByte}
+	frame = binary.BigEndian.AppendUint64(frame, uint64(len(payload)))
+	return append(frame, payload...)
+}
+
+func TestJitFailure(t *testing.T) {
+	machine := &JitMachine{}
+	frame := frameFailure(JitErrorTimeout, "took too long")
+	_, err := machine.readResult(bytes.NewReader(frame))
+
+	var jitErr *JitError
+	if !errors.As(err, &jitErr) {
+		t.Fatal("expected a JitError, got", err)
+	}
+	if jitErr.Code != JitErrorTimeout {
+		t.Fatal("wrong code", jitErr.Code)
+	}
+	if jitErr.Message != "took too long" {
+		t.Fatal("wrong message", jitErr.Message)
+	}
+
+	// a failure too short to hold a code isn't a JitError
+	frame = append([]byte{failureByte}, binary.BigEndian.AppendUint64(nil, 1)...)
+	frame = append(frame, 0)
+	_, err = machine.readResult(bytes.NewReader(frame))
+	if err == nil || errors.As(err, &jitErr) {
+		t.Fatal("expected a communication failure, got", err)
+	}
+}
+
+func TestJitSuccess(t *testing.T) {
+	machine := &JitMachine{wasmMemoryUsageLimit: 1 << 30}
+	blockHash := common.HexToHash("0x0b")
+	sendRoot := common.HexToHash("0xabab")
+
+	frame := []byte{successByte}
+	frame = binary.BigEndian.AppendUint64(frame, 3)
+	frame = binary.BigEndian.AppendUint64(frame, 7)
+	frame = append(frame, blockHash[:]...)
+	frame = append(frame, sendRoot[:]...)
+	frame = binary.BigEndian.AppendUint64(frame, 1<<20)
+
+	state, err := machine.readResult(bytes.NewReader(frame))
+	if err != nil {
+		t.Fatal(err)
+	}
+	if state.Batch != 3 || state.PosInBatch != 7 {
+		t.Fatal("wrong positions", state.Batch, state.PosInBatch)
+	}
+	if state.BlockHash != blockHash || state.SendRoot != sendRoot {
+		t.Fatal("wrong hashes", state.BlockHash, state.SendRoot)
+	}
+}
+
+func TestJitErrorCodeString(t *testing.T) {
+	if JitErrorProtocolViolation.String() != "ProtocolViolation" {
+		t.Fatal("wrong name", JitErrorProtocolViolation.String())
+	}
+	if JitErrorCode(100).String() != "JitErrorCode(100)" {
+		t.Fatal("wrong name", JitErrorCode(100).String())
+	}
+}